@@ -3,32 +3,114 @@
 //! Reads from build output (website/build/prerendered/) to include all dynamic content.
 //! Auto-discovers all pages from the website's navigation.ts config.
 //! Large documents are automatically chunked at H2 headers for better AI consumption.
+//! Chunk budgets are measured in LLM tokens using the bundled cl100k BPE vocabulary.
+//! Sections that are still too large are split again at H3, then H4, then paragraph
+//! boundaries, producing nested chunk ids such as `deserialize/validation/string-validators`.
+//! Chunking works on the CommonMark block structure, so headings inside code blocks,
+//! tables, lists or HTML blocks never start a new chunk.
 //!
-//! Usage: rust-script scripts/extract-docs.rs
+//! Each chunk repeats some overlap from the preceding content and, with `--context`, a
+//! header with a breadcrumb and the page summary, so chunks still make sense when
+//! retrieved alone. Chunks are sized by their emitted text, header and overlap included.
+//!
+//! Every page and chunk gets a deterministic extractive summary for the section index.
+//! Keywords are the configured use cases and frontmatter `keywords`/`tags`, topped up
+//! with TF-IDF terms computed over the whole extracted corpus; each records its source.
+//! A BM25 inverted index over titles, headings, keywords and body text is written to
+//! `search-index.json` so the server can rank results without rescanning content.
+//! Hashed TF-IDF vectors for every section go to `vectors.json` for cosine similarity
+//! search, computed offline with no model or network access.
+//! `aliases.json` maps spellings of page titles, hrefs and code identifiers, plus the
+//! configured synonyms and renamed ids, to canonical section ids.
+//! Every fenced code block is catalogued in `examples.json` with its language, kind,
+//! owning section, nearest heading and the sentence that introduces it. Input samples
+//! and the expanded output shown after them are parsed as TypeScript and linked into
+//! `example-pairs.json`.
+//! The builtin-macros pages are parsed into a `macros.json` catalog of each macro's
+//! targets, generated methods and decorator options, linked back to their sections.
+//! The `@serde` options and validators are merged into a typed reference, `serde.json`.
+//! A macro × target support matrix goes to `macro-support.json` and is rendered as the
+//! `macro-support` section.
+//! Commands documented under headings such as `macroforge expand` are extracted into
+//! `cli.json` with their usage, arguments, flags, defaults and example invocations.
+//! The `macroforge.json` options on the configuration page become a JSON Schema,
+//! `macroforge.schema.json`; `config-report.json` lists keys that only the examples or
+//! only the option reference mention.
+//! Documented error messages, failure conditions and troubleshooting steps are collected
+//! into `errors.json` with match patterns, fixes and the sections explaining them.
+//! The `ts_template!` syntax forms and constructs go to `ts-quote-syntax.json` and are
+//! rendered as the single-chunk `ts-quote-syntax` quick reference.
+//! Exports declared in the `.d.ts` files of `node_modules/macroforge` (and `@macroforge/*`)
+//! fill `website/static/api-data/typescript/index.json` and the `typescript-api` sections.
+//! `BOOK.md` concatenates every page in navigation order into a single file with a nested
+//! table of contents, demoted headings and links rewritten to in-book anchors.
+//! Every section records a `source_map` pointing back at the source file and line range
+//! (or HTML node path for prerendered pages) of the section and each of its headings.
+//!
+//! Usage: rust-script scripts/extract-docs.rs [options]
+//! Tests: rust-script --test scripts/extract-docs.rs
+//!
+//! Options:
+//!   --check               Verify that every chunked page can be rebuilt losslessly from
+//!                         its chunks, without writing any output; exits non-zero on failure
+//!   --context             Prepend a breadcrumb/summary header to every chunk
+//!   --overlap=<mode>      Overlap repeated at the top of each chunk:
+//!                         `heading` (nearest parent heading, default),
+//!                         `paragraph` (trailing paragraph of the previous chunk) or `none`
+//!   --stem                Conflate inflected forms (`macros`/`macro`) when extracting keywords
+//!   --rustdoc=<dir>       Add the public items of every rustdoc JSON file in <dir> (e.g.
+//!                         `target/doc` after `cargo +nightly rustdoc -- -Z unstable-options
+//!                         --output-format json`) as "Rust API" sections
+//!   --book-max-tokens=<n> Also write `BOOK-capped.md`, abridged to about <n> tokens
+//!   --llms                Also write `llms.txt` (summary and categorized page links) and
+//!                         `llms-full.txt` (every page's markdown), in navigation order
 //!
 //! ```cargo
 //! [dependencies]
 //! htmd = "0.5"
+//! oxc_allocator = "0.110"
+//! oxc_ast = "0.110"
+//! oxc_parser = "0.110"
+//! oxc_span = "0.110"
+//! pulldown-cmark = { version = "0.13", default-features = false }
 //! scraper = "0.18"
 //! regex = "1"
 //! serde = { version = "1", features = ["derive"] }
 //! serde_json = "1"
+//! tiktoken-rs = "0.7"
 //! ```
 
 use htmd::HtmlToMarkdown;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    ClassElement, Declaration, Statement, TSModuleDeclarationBody, TSModuleDeclarationName,
+    TSSignature,
+};
+use oxc_parser::Parser as TsParser;
+use oxc_span::{GetSpan, SourceType};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tiktoken_rs::cl100k_base_singleton;
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-const CHUNK_SIZE_THRESHOLD: usize = 6000;
-const MIN_CHUNK_SIZE: usize = 500;
+/// Pages and sections above this many tokens are split into chunks
+const CHUNK_TOKEN_BUDGET: usize = 1500;
+/// Chunks below this many tokens are merged into their previous sibling
+const MIN_CHUNK_TOKENS: usize = 125;
+/// Deepest heading level used for splitting before falling back to paragraph boundaries
+const MAX_SPLIT_HEADING_LEVEL: usize = 4;
+/// Number of TF-IDF keywords added to every section
+const EXTRACTED_KEYWORDS: usize = 5;
+/// Number of the page's configured keywords inherited by each chunk
+const INHERITED_KEYWORDS: usize = 2;
 
 // ============================================================================
 // Types
@@ -53,20 +135,199 @@ struct DocSection {
     category: String,
     category_title: String,
     path: String,
+    /// Comma-separated keywords (the terms of `keywords`)
     use_cases: String,
+    keywords: Vec<Keyword>,
+    /// Extractive summary of the section content
+    summary: String,
+    /// Token count of the section content (the whole page for chunked parents)
+    tokens: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     is_chunked: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     chunk_ids: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     parent_id: Option<String>,
+    source_map: SourceMap,
+    /// Section markdown without the context header, used for keyword extraction
+    #[serde(skip)]
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Keyword {
+    term: String,
+    source: KeywordSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum KeywordSource {
+    /// From `get_use_cases_map`
+    Manual,
+    /// From the page frontmatter
+    Frontmatter,
+    /// Computed with TF-IDF over the extracted corpus
+    Extracted,
+}
+
+/// Location of a section in the website sources, so citations can point at file:line.
+#[derive(Debug, Serialize)]
+struct SourceMap {
+    /// Source file relative to the repository root
+    file: String,
+    /// First and last source line (1-based, inclusive) for mdsvex pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<(usize, usize)>,
+    /// HTML node path for prerendered pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_path: Option<String>,
+    headings: Vec<HeadingSource>,
+}
+
+#[derive(Debug, Serialize)]
+struct HeadingSource {
+    level: usize,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_path: Option<String>,
+}
+
+/// A page's markdown together with what is needed to map it back to its source.
+struct PageSource {
+    markdown: String,
+    /// Source file relative to the repository root
+    file: String,
+    origin: SourceOrigin,
+}
+
+enum SourceOrigin {
+    /// mdsvex page: the source byte offset of every markdown byte, plus the byte
+    /// offset at which each source line starts
+    Mdsvex { offsets: Vec<usize>, line_starts: Vec<usize> },
+    /// Prerendered HTML: node path of the prose root and `(text, node path)` of each
+    /// heading in document order
+    Prerendered { root_path: String, headings: Vec<(String, String)> },
 }
 
 #[derive(Debug)]
 struct Chunk {
+    /// Slug path relative to the page, e.g. `validation/string-validators`
     slug: String,
     title: String,
     content: String,
+    /// Byte range of the chunk within the page markdown
+    start: usize,
+    end: usize,
+    /// Heading text (or synthetic label such as "Overview") used in breadcrumbs
+    heading: String,
+    /// Overlap repeated from the preceding content, per `ExtractOptions::overlap`
+    overlap: Option<String>,
+    /// Sub-chunks when this chunk was still too large and split again
+    children: Vec<Chunk>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum ChunkOverlap {
+    None,
+    /// The trailing paragraph of the previous chunk
+    Paragraph,
+    /// The text of the nearest parent heading
+    #[default]
+    Heading,
+}
+
+#[derive(Debug, Default)]
+struct ExtractOptions {
+    /// Verify chunking instead of writing output
+    check: bool,
+    /// Prepend a breadcrumb and page summary header to every chunk
+    context_header: bool,
+    overlap: ChunkOverlap,
+    /// Stem words before computing keyword frequencies
+    stem: bool,
+    /// Directory of rustdoc JSON files to add as Rust API sections
+    rustdoc_dir: Option<PathBuf>,
+    /// Token budget of the abridged `BOOK-capped.md`, written only when set
+    book_max_tokens: Option<usize>,
+    /// Also write `llms.txt` and `llms-full.txt`
+    llms: bool,
+}
+
+/// Page-level metadata shared by every chunk emitted for a page.
+struct PageInfo<'a> {
+    item_id: String,
+    title: String,
+    category: String,
+    category_title: String,
+    /// Configured keywords of the page, some of which are inherited by chunks
+    keywords: Vec<Keyword>,
+    /// Summary of the page used in chunk context headers
+    summary: String,
+    source: &'a PageSource,
+    blocks: &'a [Block],
+}
+
+/// A top-level markdown block and its byte range within the page markdown.
+#[derive(Debug)]
+struct Block {
+    start: usize,
+    end: usize,
+    kind: BlockKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BlockKind {
+    Heading { level: usize, text: String },
+    Paragraph,
+    CodeBlock,
+    List,
+    Table,
+    BlockQuote,
+    Html,
+    Rule,
+    Metadata,
+    Other,
+}
+
+// ============================================================================
+// Options
+// ============================================================================
+
+fn parse_options() -> ExtractOptions {
+    let mut options = ExtractOptions::default();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => options.check = true,
+            "--context" => options.context_header = true,
+            "--overlap=none" => options.overlap = ChunkOverlap::None,
+            "--overlap=paragraph" => options.overlap = ChunkOverlap::Paragraph,
+            "--overlap=heading" => options.overlap = ChunkOverlap::Heading,
+            "--stem" => options.stem = true,
+            "--llms" => options.llms = true,
+            other if other.starts_with("--rustdoc=") => {
+                options.rustdoc_dir = Some(PathBuf::from(&other["--rustdoc=".len()..]));
+            }
+            other if other.starts_with("--book-max-tokens=") => {
+                match other["--book-max-tokens=".len()..].parse() {
+                    Ok(max_tokens) => options.book_max_tokens = Some(max_tokens),
+                    Err(_) => {
+                        eprintln!("Invalid token count: {}", other);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    options
 }
 
 // ============================================================================
@@ -143,6 +404,39 @@ fn get_category_id_map() -> HashMap<&'static str, &'static str> {
     m
 }
 
+// ============================================================================
+// Alias Config
+// ============================================================================
+
+/// Extra phrases users say for a page, keyed by href. Titles, hrefs and code
+/// identifiers are picked up automatically and do not need to be listed.
+fn get_synonyms_map() -> HashMap<&'static str, &'static str> {
+    let mut m = HashMap::new();
+
+    m.insert("/docs/builtin-macros/debug", "to string, stringify, print object, inspect");
+    m.insert("/docs/builtin-macros/clone", "copy, deep copy, duplicate, deep clone");
+    m.insert("/docs/builtin-macros/default", "default value, default values, factory, zero value");
+    m.insert("/docs/builtin-macros/hash", "hash code, hashing");
+    m.insert("/docs/builtin-macros/ord", "total ordering, sort, sorting");
+    m.insert("/docs/builtin-macros/partial-eq", "equals, equality, value equality, structural equality");
+    m.insert("/docs/builtin-macros/partial-ord", "partial ordering, compare, comparison");
+    m.insert("/docs/builtin-macros/serialize", "to json, serialization, stringify json");
+    m.insert("/docs/builtin-macros/deserialize", "from json, deserialization, parse json, validation");
+    m.insert("/docs/custom-macros/ts-quote", "template, ts template, quote, codegen template");
+    m.insert("/docs/integration/configuration", "config, config file, settings");
+    m.insert("/docs/integration/cli", "command line, cli, terminal");
+    m.insert("/docs/integration/mcp-server", "mcp, model context protocol");
+
+    m
+}
+
+/// Section ids that were renamed, mapped to their current id, so old links resolve.
+fn get_renamed_ids_map() -> HashMap<&'static str, &'static str> {
+    let mut m = HashMap::new();
+    m.insert("serde-validators", "deserialize/validation");
+    m
+}
+
 // ============================================================================
 // Navigation Parsing
 // ============================================================================
@@ -197,6 +491,52 @@ fn href_to_id(href: &str, category_map: &HashMap<&str, &str>) -> String {
     }
 }
 
+/// Assigns a unique section id to every navigation item, keyed by href.
+///
+/// Ids default to the last path segment. When several pages share a leaf name, all
+/// of them fall back to their full path joined with hyphens (`integration-svelte`),
+/// and any remaining clash gets a numeric suffix in navigation order.
+fn assign_page_ids(
+    navigation: &[NavSection],
+    category_map: &HashMap<&str, &str>,
+) -> HashMap<String, String> {
+    let hrefs: Vec<&str> = navigation
+        .iter()
+        .flat_map(|s| s.items.iter().map(|i| i.href.as_str()))
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for href in &hrefs {
+        *counts.entry(href_to_id(href, category_map)).or_default() += 1;
+    }
+
+    let mut used = HashSet::new();
+    let mut ids = HashMap::new();
+    for href in hrefs {
+        let id = href_to_id(href, category_map);
+        let id = if counts[&id] > 1 { href_to_qualified_id(href) } else { id };
+        ids.insert(href.to_string(), unique_slug(&id, &mut used));
+    }
+    ids
+}
+
+fn href_to_qualified_id(href: &str) -> String {
+    let path = href.strip_prefix("/docs/").unwrap_or(href);
+    path.trim_matches('/').replace('/', "-")
+}
+
+/// Returns `base` if it is unused, otherwise the first free `base-2`, `base-3`, ...
+fn unique_slug(base: &str, used: &mut HashSet<String>) -> String {
+    let mut slug = base.to_string();
+    let mut n = 2;
+    while used.contains(&slug) {
+        slug = format!("{}-{}", base, n);
+        n += 1;
+    }
+    used.insert(slug.clone());
+    slug
+}
+
 fn href_to_prerendered_path(href: &str, website_dir: &Path) -> PathBuf {
     let path = href.strip_prefix("/").unwrap_or(href);
     website_dir.join("build/prerendered").join(path).with_extension("html")
@@ -211,30 +551,63 @@ fn href_to_source_path(href: &str, website_dir: &Path) -> PathBuf {
 // Markdown Processing
 // ============================================================================
 
-fn strip_mdsvex_boilerplate(markdown: &str) -> String {
-    let mut md = markdown.to_string();
+/// Strips mdsvex boilerplate, returning the markdown and, for every byte of it, the
+/// byte offset it came from in the source file.
+fn strip_mdsvex_boilerplate(markdown: &str) -> (String, Vec<usize>) {
+    let mut removed: Vec<(usize, usize)> = Vec::new();
 
     // Remove leading HTML comments
     let comment_re = Regex::new(r"^<!--[\s\S]*?-->\s*").unwrap();
-    md = comment_re.replace(&md, "").to_string();
+    let body_start = comment_re.find(markdown).map_or(0, |m| m.end());
+    removed.push((0, body_start));
 
     // Remove svelte:head blocks
     let head_re = Regex::new(r"<svelte:head>[\s\S]*?</svelte:head>\s*").unwrap();
-    md = head_re.replace_all(&md, "").to_string();
+    for m in head_re.find_iter(&markdown[body_start..]) {
+        removed.push((body_start + m.start(), body_start + m.end()));
+    }
+    removed.push((markdown.len(), markdown.len()));
 
-    md.trim().to_string() + "\n"
+    let mut md = String::new();
+    let mut offsets = Vec::new();
+    let mut pos = 0;
+    for (start, end) in removed {
+        md.push_str(&markdown[pos..start]);
+        offsets.extend(pos..start);
+        pos = end;
+    }
+
+    let lead = md.len() - md.trim_start().len();
+    let tail = md.trim_end().len().max(lead);
+    let mut result = md[lead..tail].to_string();
+    let mut offsets = offsets[lead..tail].to_vec();
+    result.push('\n');
+    offsets.push(markdown.len());
+    (result, offsets)
 }
 
-fn read_markdown_source(href: &str, website_dir: &Path) -> Option<String> {
+fn read_markdown_source(href: &str, website_dir: &Path, repo_root: &Path) -> Option<PageSource> {
     let source_path = href_to_source_path(href, website_dir);
     if source_path.exists() {
         let content = fs::read_to_string(&source_path).ok()?;
-        Some(strip_mdsvex_boilerplate(&content))
+        let (markdown, offsets) = strip_mdsvex_boilerplate(&content);
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Some(PageSource {
+            markdown,
+            file: relative_path(&source_path, repo_root),
+            origin: SourceOrigin::Mdsvex { offsets, line_starts },
+        })
     } else {
         None
     }
 }
 
+fn relative_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
 // ============================================================================
 // HTML to Markdown Conversion
 // ============================================================================
@@ -270,6 +643,63 @@ fn html_to_markdown(html: &str) -> String {
     md
 }
 
+fn read_prerendered_page(html_path: &Path, repo_root: &Path) -> PageSource {
+    let raw_html = fs::read_to_string(html_path).unwrap_or_default();
+    let (root_path, headings) = prerendered_headings(&raw_html);
+    PageSource {
+        markdown: html_to_markdown(&raw_html),
+        file: relative_path(html_path, repo_root),
+        origin: SourceOrigin::Prerendered { root_path, headings },
+    }
+}
+
+/// Collects the node path of the prose root and the `(text, node path)` of every
+/// heading inside it, e.g. `div.prose > h2:nth-of-type(3)`.
+fn prerendered_headings(html: &str) -> (String, Vec<(String, String)>) {
+    let document = Html::parse_document(html);
+    let root = ["div.prose", "article"].into_iter().find_map(|label| {
+        let selector = Selector::parse(label).ok()?;
+        document.select(&selector).next().map(|element| (label, element))
+    });
+    let Some((root_label, root)) = root else {
+        return (String::new(), Vec::new());
+    };
+
+    let heading_selector = Selector::parse("h1, h2, h3, h4, h5, h6").unwrap();
+    let headings = root
+        .select(&heading_selector)
+        .map(|heading| {
+            let text = heading.text().collect::<Vec<_>>().join("");
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            (text, node_path(heading, root, root_label))
+        })
+        .collect();
+
+    (root_label.to_string(), headings)
+}
+
+fn node_path(element: ElementRef, root: ElementRef, root_label: &str) -> String {
+    let mut segments = Vec::new();
+    let mut current = element;
+    while current.id() != root.id() {
+        let name = current.value().name();
+        let index = current
+            .prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().name() == name)
+            .count()
+            + 1;
+        segments.push(format!("{}:nth-of-type({})", name, index));
+        match current.parent().and_then(ElementRef::wrap) {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    segments.push(root_label.to_string());
+    segments.reverse();
+    segments.join(" > ")
+}
+
 fn cleanup_markdown(md: &str) -> String {
     let mut result = md.to_string();
 
@@ -292,6 +722,84 @@ fn cleanup_markdown(md: &str) -> String {
     result.trim().to_string()
 }
 
+// ============================================================================
+// Markdown Structure
+// ============================================================================
+
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+}
+
+/// Parses markdown into its top-level CommonMark blocks.
+///
+/// Only ATX and setext headings that are real top-level blocks become `Heading`
+/// blocks; `## ` lines inside fences, lists, block quotes or HTML blocks stay part
+/// of their enclosing block.
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut current: Option<(usize, BlockKind)> = None;
+    let mut heading_text = String::new();
+
+    for (event, range) in Parser::new_ext(markdown, markdown_options()).into_offset_iter() {
+        let in_heading = matches!(current, Some((_, BlockKind::Heading { .. })));
+
+        match event {
+            Event::Start(tag) => {
+                if depth == 0 {
+                    let kind = match tag {
+                        Tag::Heading { level, .. } => BlockKind::Heading {
+                            level: level as usize,
+                            text: String::new(),
+                        },
+                        Tag::Paragraph => BlockKind::Paragraph,
+                        Tag::CodeBlock(_) => BlockKind::CodeBlock,
+                        Tag::List(_) => BlockKind::List,
+                        Tag::Table(_) => BlockKind::Table,
+                        Tag::BlockQuote(_) => BlockKind::BlockQuote,
+                        Tag::HtmlBlock => BlockKind::Html,
+                        Tag::MetadataBlock(_) => BlockKind::Metadata,
+                        _ => BlockKind::Other,
+                    };
+                    current = Some((range.start, kind));
+                    heading_text.clear();
+                }
+                depth += 1;
+            }
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some((start, mut kind)) = current.take() {
+                        if let BlockKind::Heading { text, .. } = &mut kind {
+                            *text = heading_text.trim().to_string();
+                        }
+                        blocks.push(Block { start, end: range.end, kind });
+                    }
+                }
+            }
+            Event::Rule if depth == 0 => {
+                blocks.push(Block { start: range.start, end: range.end, kind: BlockKind::Rule });
+            }
+            Event::Text(text) | Event::InlineHtml(text) if in_heading => {
+                heading_text.push_str(&text)
+            }
+            Event::Code(code) if in_heading => {
+                heading_text.push('`');
+                heading_text.push_str(&code);
+                heading_text.push('`');
+            }
+            Event::SoftBreak | Event::HardBreak if in_heading => heading_text.push(' '),
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
 // ============================================================================
 // Chunking
 // ============================================================================
@@ -321,256 +829,7422 @@ fn header_to_slug(header: &str) -> String {
     slug.trim_matches('-').to_string()
 }
 
-fn extract_chunk_use_cases(content: &str, parent_use_cases: &str) -> String {
-    let mut keywords = Vec::new();
+fn chunk_markdown(page: &PageInfo, options: &ExtractOptions) -> Result<Vec<Chunk>, String> {
+    let frame = ChunkFrame { page, options };
+    let chunks = split_at_level(&frame, page.blocks, 2, &page.title, "", &[]);
+    check_balanced(&chunks, page.blocks)?;
+    Ok(chunks)
+}
 
-    // Get code-related terms
-    let code_re = Regex::new(r"`([^`]+)`").unwrap();
-    for cap in code_re.captures_iter(content).take(5) {
-        let term = cap[1].to_lowercase();
-        if term.len() > 2 && term.len() < 30 && !term.contains(' ') {
-            keywords.push(term);
-        }
+/// The page being chunked and the options that decide what is emitted around each
+/// chunk, so chunks are sized by the text that is actually written.
+struct ChunkFrame<'a> {
+    page: &'a PageInfo<'a>,
+    options: &'a ExtractOptions,
+}
+
+impl ChunkFrame<'_> {
+    fn markdown(&self) -> &str {
+        &self.page.source.markdown
     }
 
-    // Include parent use cases
-    let parent_keywords: Vec<&str> = parent_use_cases.split(',').map(|k| k.trim()).take(2).collect();
+    /// Overlap repeated at the top of a chunk, taken from the blocks preceding it.
+    fn overlap(&self, chunk: &Chunk) -> Option<String> {
+        let blocks = self.page.blocks;
+        match self.options.overlap {
+            ChunkOverlap::None => None,
+            ChunkOverlap::Paragraph => blocks
+                .iter()
+                .rev()
+                .find(|b| b.end <= chunk.start && b.kind == BlockKind::Paragraph)
+                .map(|b| self.markdown()[b.start..b.end].trim().to_string()),
+            ChunkOverlap::Heading => parent_heading(chunk, blocks),
+        }
+    }
 
-    let mut result: Vec<String> = parent_keywords.iter().map(|s| s.to_string()).collect();
-    result.extend(keywords);
-    result.dedup();
-    result.truncate(6);
-    result.join(", ")
+    /// Tokens of the chunk as emitted, overlap and context header included.
+    fn emitted_tokens(&self, chunk: &Chunk, trail: &[String]) -> usize {
+        count_tokens(&with_context(chunk, self.page, trail, self.options))
+    }
 }
 
-fn chunk_markdown(markdown: &str, parent_title: &str) -> Vec<Chunk> {
-    let mut chunks = Vec::new();
+/// Whether chunking actually divided the page. A page with a single H2 (or none)
+/// yields one top-level chunk whose children carry the split.
+fn is_split(chunks: &[Chunk]) -> bool {
+    chunks.len() > 1 || chunks.first().is_some_and(|chunk| !chunk.children.is_empty())
+}
 
-    let h2_re = Regex::new(r"(?m)^## (.+)$").unwrap();
-    let parts: Vec<&str> = h2_re.split(markdown).collect();
-    let headers: Vec<String> = h2_re.captures_iter(markdown).map(|c| c[1].to_string()).collect();
+/// Text of the blocks in `start..end`, sliced from the start of the first line so an
+/// indented code block keeps the indentation that makes it one.
+fn chunk_text(markdown: &str, start: usize, end: usize) -> &str {
+    let line_start = markdown[..start].rfind('\n').map_or(0, |i| i + 1);
+    markdown[line_start..end].trim_end()
+}
 
-    // First part is content before any H2
-    if !parts.is_empty() && parts[0].trim().len() >= MIN_CHUNK_SIZE {
-        chunks.push(Chunk {
-            slug: "overview".to_string(),
-            title: format!("{}: Overview", parent_title),
-            content: parts[0].trim().to_string(),
-        });
-    } else if !parts.is_empty() && !parts[0].trim().is_empty() {
-        chunks.push(Chunk {
-            slug: "_intro".to_string(),
-            title: String::new(),
-            content: parts[0].trim().to_string(),
-        });
+/// Groups blocks into runs that start at each heading of exactly `level`.
+/// The first run has no header when content precedes the first heading.
+fn group_blocks(blocks: &[Block], level: usize) -> Vec<(Option<String>, &[Block])> {
+    let mut groups: Vec<(Option<String>, usize, usize)> = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        match &block.kind {
+            BlockKind::Heading { level: l, text } if *l == level => {
+                groups.push((Some(text.clone()), i, i + 1))
+            }
+            _ => match groups.last_mut() {
+                Some(group) => group.2 = i + 1,
+                None => groups.push((None, i, i + 1)),
+            },
+        }
     }
+    groups.into_iter().map(|(header, first, last)| (header, &blocks[first..last])).collect()
+}
+
+fn split_at_level(
+    frame: &ChunkFrame,
+    blocks: &[Block],
+    level: usize,
+    parent_title: &str,
+    slug_prefix: &str,
+    trail: &[String],
+) -> Vec<Chunk> {
+    let markdown = frame.markdown();
+    let top_level = slug_prefix.is_empty();
+    let mut chunks: Vec<Chunk> = Vec::new();
 
-    // Process header/content pairs
-    for (i, header) in headers.iter().enumerate() {
-        let content = parts.get(i + 1).map(|s| s.trim()).unwrap_or("");
-        let slug = header_to_slug(header);
-        let full_content = format!("## {}\n\n{}", header, content);
+    for (header, group) in group_blocks(blocks, level) {
+        let start = group[0].start;
+        let end = group[group.len() - 1].end;
+        let content = chunk_text(markdown, start, end);
+
+        let Some(header) = header else {
+            // Content before the first heading at this level
+            let (slug, title) = if count_tokens(content) >= MIN_CHUNK_TOKENS {
+                (format!("{}overview", slug_prefix), format!("{}: Overview", parent_title))
+            } else {
+                ("_intro".to_string(), String::new())
+            };
+            chunks.push(Chunk {
+                slug,
+                title,
+                content: content.to_string(),
+                start,
+                end,
+                heading: "Overview".to_string(),
+                overlap: None,
+                children: Vec::new(),
+            });
+            continue;
+        };
 
         // Merge small chunks with previous
-        if full_content.len() < MIN_CHUNK_SIZE && !chunks.is_empty() {
+        if count_tokens(content) < MIN_CHUNK_TOKENS && !chunks.is_empty() {
             let last_idx = chunks.len() - 1;
             if chunks[last_idx].slug != "_intro" {
-                chunks[last_idx].content.push_str("\n\n");
-                chunks[last_idx].content.push_str(&full_content);
+                extend_chunk(&mut chunks[last_idx], markdown, end);
                 continue;
             }
         }
 
+        let slug = match header_to_slug(&header) {
+            slug if slug.is_empty() => "section".to_string(),
+            slug => slug,
+        };
         chunks.push(Chunk {
-            slug,
+            slug: format!("{}{}", slug_prefix, slug),
             title: format!("{}: {}", parent_title, header),
-            content: full_content,
+            content: content.to_string(),
+            start,
+            end,
+            heading: header,
+            overlap: None,
+            children: Vec::new(),
         });
     }
 
-    // Handle intro content
+    // Handle intro content. A page's first chunk is always its overview; nested
+    // sections keep the slug of the sub-section the intro is merged into.
     if chunks.len() > 1 && chunks[0].slug == "_intro" {
         let intro = chunks.remove(0);
-        chunks[0].content = format!("{}\n\n{}", intro.content, chunks[0].content);
-        chunks[0].slug = "overview".to_string();
-        chunks[0].title = format!("{}: Overview", parent_title);
+        chunks[0].start = intro.start;
+        chunks[0].content = chunk_text(markdown, intro.start, chunks[0].end).to_string();
+        if top_level {
+            chunks[0].slug = "overview".to_string();
+            chunks[0].title = format!("{}: Overview", parent_title);
+            chunks[0].heading = "Overview".to_string();
+        }
     } else if chunks.len() == 1 && chunks[0].slug == "_intro" {
-        chunks[0].slug = "overview".to_string();
+        chunks[0].slug = format!("{}overview", slug_prefix);
         chunks[0].title = format!("{}: Overview", parent_title);
     }
 
-    chunks
-}
-
-fn should_chunk(content: &str) -> bool {
-    content.len() > CHUNK_SIZE_THRESHOLD
-}
-
-// ============================================================================
-// Main
-// ============================================================================
-
-fn main() {
-    // Paths - script runs from repo root or from packages/mcp-server
-    let cwd = std::env::current_dir().unwrap();
+    // Disambiguate sibling slugs in document order, e.g. two "Example" headings or
+    // a real "## Overview" next to the synthetic overview chunk
+    let mut used = HashSet::new();
+    for chunk in &mut chunks {
+        chunk.slug = unique_slug(&chunk.slug, &mut used);
+    }
 
-    // Determine repo root - check for pixi.toml to confirm we're at root
-    let repo_root = if cwd.join("pixi.toml").exists() {
-        cwd.clone()
-    } else {
-        // Try to find repo root by looking for pixi.toml
-        let mut candidate = cwd.clone();
-        loop {
-            if candidate.join("pixi.toml").exists() {
-                break candidate;
-            }
-            match candidate.parent() {
-                Some(parent) => candidate = parent.to_path_buf(),
-                None => break cwd.clone(), // Give up and use cwd
+    // Recursively split chunks whose emitted text still exceeds the budget
+    for chunk in &mut chunks {
+        chunk.overlap = frame.overlap(chunk);
+        if frame.emitted_tokens(chunk, trail) > CHUNK_TOKEN_BUDGET {
+            let inner = chunk_blocks(blocks, chunk);
+            let prefix = format!("{}/", chunk.slug);
+            let mut child_trail = trail.to_vec();
+            child_trail.push(chunk.heading.clone());
+            let children =
+                subdivide(frame, inner, level + 1, &chunk.title, &prefix, &child_trail);
+            if children.len() > 1 {
+                chunk.children = children;
             }
         }
-    };
+    }
 
-    let website_dir = repo_root.join("website");
-    let prerendered_dir = website_dir.join("build/prerendered");
-    let navigation_path = website_dir.join("src/lib/config/navigation.ts");
-    let output_dir = repo_root.join("packages/mcp-server/docs");
+    chunks
+}
 
-    // Check prerendered exists
-    let has_prerendered = prerendered_dir.exists();
-    if !has_prerendered {
-        eprintln!("Warning: Prerendered directory not found: {:?}", prerendered_dir);
-        eprintln!("Falling back to mdsvex source pages when available.\n");
+/// Splits an oversized section at the next heading level that actually divides it,
+/// falling back to paragraph boundaries below `MAX_SPLIT_HEADING_LEVEL`.
+fn subdivide(
+    frame: &ChunkFrame,
+    blocks: &[Block],
+    from_level: usize,
+    parent_title: &str,
+    slug_prefix: &str,
+    trail: &[String],
+) -> Vec<Chunk> {
+    for level in from_level..=MAX_SPLIT_HEADING_LEVEL {
+        let has_headings = blocks
+            .iter()
+            .any(|b| matches!(&b.kind, BlockKind::Heading { level: l, .. } if *l == level));
+        if has_headings {
+            let chunks = split_at_level(frame, blocks, level, parent_title, slug_prefix, trail);
+            if chunks.len() > 1 {
+                return chunks;
+            }
+        }
     }
 
-    println!("Auto-discovering pages from navigation.ts...\n");
+    split_at_paragraphs(frame, blocks, parent_title, slug_prefix, trail)
+}
 
-    // Parse navigation
-    let navigation = parse_navigation(&navigation_path);
-    println!("Found {} sections in navigation.ts\n", navigation.len());
+/// Greedily packs whole blocks into parts whose emitted text, overlap included, fits
+/// the token budget. A single block larger than the budget (e.g. a long code sample)
+/// is kept intact.
+fn split_at_paragraphs(
+    frame: &ChunkFrame,
+    blocks: &[Block],
+    parent_title: &str,
+    slug_prefix: &str,
+    trail: &[String],
+) -> Vec<Chunk> {
+    let markdown = frame.markdown();
+    let part = |i: usize, start: usize, end: usize| {
+        let mut chunk = Chunk {
+            slug: format!("{}part-{}", slug_prefix, i + 1),
+            title: format!("{} (part {})", parent_title, i + 1),
+            content: chunk_text(markdown, start, end).to_string(),
+            start,
+            end,
+            heading: format!("Part {}", i + 1),
+            overlap: None,
+            children: Vec::new(),
+        };
+        chunk.overlap = frame.overlap(&chunk);
+        chunk
+    };
+    let fits = |i: usize, start: usize, end: usize| {
+        frame.emitted_tokens(&part(i, start, end), trail) <= CHUNK_TOKEN_BUDGET
+    };
 
-    // Ensure output directory exists
-    if let Err(e) = fs::create_dir_all(&output_dir) {
-        eprintln!("Failed to create output directory: {}", e);
-        std::process::exit(1);
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut first = 0;
+    for i in 0..blocks.len() {
+        if i > first && !fits(ranges.len(), blocks[first].start, blocks[i].end) {
+            ranges.push((blocks[first].start, blocks[i - 1].end));
+            first = i;
+        }
+    }
+    if let Some(last) = blocks.last() {
+        let start = blocks[first].start;
+        let small = count_tokens(chunk_text(markdown, start, last.end)) < MIN_CHUNK_TOKENS;
+        let previous = ranges.len().saturating_sub(1);
+        match ranges.last_mut() {
+            // Merge a small trailing part with the previous part if the result still fits
+            Some(prev) if small && fits(previous, prev.0, last.end) => prev.1 = last.end,
+            _ => ranges.push((start, last.end)),
+        }
     }
 
-    let use_cases_map = get_use_cases_map();
-    let category_id_map = get_category_id_map();
-    let mut sections: Vec<DocSection> = Vec::new();
+    ranges.into_iter().enumerate().map(|(i, (start, end))| part(i, start, end)).collect()
+}
 
-    for section in &navigation {
-        let category = href_to_category(section.items.first().map(|i| i.href.as_str()).unwrap_or(""));
+/// Returns the contiguous run of blocks a chunk was cut from.
+fn chunk_blocks<'a>(blocks: &'a [Block], chunk: &Chunk) -> &'a [Block] {
+    let first = blocks.iter().position(|b| b.start >= chunk.start).unwrap_or(blocks.len());
+    let last = blocks.iter().rposition(|b| b.end <= chunk.end).map_or(first, |i| i + 1);
+    &blocks[first..last.max(first)]
+}
 
-        // Create category directory
-        let category_dir = output_dir.join(&category);
-        fs::create_dir_all(&category_dir).ok();
+fn extend_chunk(chunk: &mut Chunk, markdown: &str, end: usize) {
+    chunk.end = end;
+    chunk.content = chunk_text(markdown, chunk.start, end).to_string();
+}
 
-        for item in &section.items {
-            let item_id = href_to_id(&item.href, &category_id_map);
-            println!("Processing: {} ({})", item.title, item.href);
+/// Chunks are cut at top-level block boundaries, so re-parsing a chunk must yield
+/// exactly the blocks it was cut from. Anything else means a fence, table, list or
+/// HTML block was split, which would hand broken markdown to the model.
+fn check_balanced(chunks: &[Chunk], page_blocks: &[Block]) -> Result<(), String> {
+    for chunk in chunks {
+        check_chunk_balanced(chunk, page_blocks)?;
+        check_balanced(&chunk.children, page_blocks)?;
+    }
+    Ok(())
+}
 
-            // Try source markdown first
-            let markdown_content = match read_markdown_source(&item.href, &website_dir) {
-                Some(md) => md,
-                None => {
-                    if !has_prerendered {
-                        eprintln!("Warning: No source markdown for {} and no prerendered HTML", item.href);
-                        continue;
-                    }
+fn check_chunk_balanced(chunk: &Chunk, page_blocks: &[Block]) -> Result<(), String> {
+    let expected: Vec<&BlockKind> = page_blocks
+        .iter()
+        .filter(|b| b.start >= chunk.start && b.end <= chunk.end)
+        .map(|b| &b.kind)
+        .collect();
+    let reparsed = parse_blocks(&chunk.content);
+    let actual: Vec<&BlockKind> = reparsed.iter().map(|b| &b.kind).collect();
 
-                    let html_path = href_to_prerendered_path(&item.href, &website_dir);
-                    if !html_path.exists() {
-                        eprintln!("Warning: File not found: {:?}", html_path);
-                        continue;
-                    }
+    // Report the first block that differs, by kind rather than by count
+    let Some(i) = (0..expected.len().max(actual.len())).find(|&i| expected.get(i) != actual.get(i))
+    else {
+        return Ok(());
+    };
+    Err(format!(
+        "chunk '{}' is not balanced markdown: block {} should be {} but re-parses as {}",
+        chunk.slug,
+        i + 1,
+        block_kind_name(expected.get(i).copied()),
+        block_kind_name(actual.get(i).copied())
+    ))
+}
 
-                    let raw_html = fs::read_to_string(&html_path).unwrap_or_default();
-                    html_to_markdown(&raw_html)
-                }
-            };
+fn block_kind_name(kind: Option<&BlockKind>) -> String {
+    let name = match kind {
+        None => "nothing",
+        Some(BlockKind::Heading { level, text }) => return format!("an H{} \"{}\"", level, text),
+        Some(BlockKind::Paragraph) => "a paragraph",
+        Some(BlockKind::CodeBlock) => "a code block",
+        Some(BlockKind::List) => "a list",
+        Some(BlockKind::Table) => "a table",
+        Some(BlockKind::BlockQuote) => "a block quote",
+        Some(BlockKind::Html) => "an HTML block",
+        Some(BlockKind::Rule) => "a thematic break",
+        Some(BlockKind::Metadata) => "a metadata block",
+        Some(BlockKind::Other) => "another block",
+    };
+    name.to_string()
+}
 
-            let use_cases = use_cases_map.get(item.href.as_str())
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| item.title.to_lowercase());
+// ============================================================================
+// Chunk Context
+// ============================================================================
 
-            // Check if we need to chunk
-            if should_chunk(&markdown_content) {
-                let chunks = chunk_markdown(&markdown_content, &item.title);
+const CONTEXT_START: &str = "<!-- context -->";
+const CONTEXT_END: &str = "<!-- /context -->";
 
-                if chunks.len() > 1 {
-                    println!("  → Chunking into {} parts", chunks.len());
+/// Finds the nearest preceding heading that is shallower than the heading the chunk
+/// opens with (any preceding heading if the chunk does not open with one).
+fn parent_heading(chunk: &Chunk, blocks: &[Block]) -> Option<String> {
+    let own_level = match chunk_blocks(blocks, chunk).first().map(|b| &b.kind) {
+        Some(BlockKind::Heading { level, .. }) => *level,
+        _ => usize::MAX,
+    };
 
-                    // Create chunk directory
-                    let chunk_dir = category_dir.join(&item_id);
-                    fs::create_dir_all(&chunk_dir).ok();
-
-                    let mut chunk_ids = Vec::new();
-
-                    for chunk in &chunks {
-                        let chunk_id = format!("{}/{}", item_id, chunk.slug);
-                        let chunk_path = chunk_dir.join(format!("{}.md", chunk.slug));
-                        fs::write(&chunk_path, &chunk.content).ok();
-
-                        chunk_ids.push(chunk_id.clone());
-
-                        sections.push(DocSection {
-                            id: chunk_id,
-                            title: chunk.title.clone(),
-                            category: category.clone(),
-                            category_title: section.title.clone(),
-                            path: format!("{}/{}/{}.md", category, item_id, chunk.slug),
-                            use_cases: extract_chunk_use_cases(&chunk.content, &use_cases),
-                            is_chunked: None,
-                            chunk_ids: None,
-                            parent_id: Some(item_id.clone()),
-                        });
-                    }
+    blocks.iter().rev().filter(|b| b.end <= chunk.start).find_map(|b| match &b.kind {
+        BlockKind::Heading { level, text } if *level < own_level => Some(text.clone()),
+        _ => None,
+    })
+}
 
-                    // Add parent entry
-                    sections.push(DocSection {
-                        id: item_id.clone(),
-                        title: item.title.clone(),
-                        category: category.clone(),
-                        category_title: section.title.clone(),
-                        path: format!("{}/{}.md", category, item_id),
-                        use_cases: use_cases.clone(),
-                        is_chunked: Some(true),
-                        chunk_ids: Some(chunk_ids),
-                        parent_id: None,
-                    });
+/// Prepends the context header (breadcrumb, page summary and overlap) to a chunk.
+/// The header is fenced by HTML comments so it can be recognised and stripped.
+fn with_context(
+    chunk: &Chunk,
+    page: &PageInfo,
+    trail: &[String],
+    options: &ExtractOptions,
+) -> String {
+    let mut lines: Vec<String> = Vec::new();
 
-                    // Write full file for reference
-                    let output_path = category_dir.join(format!("{}.md", item_id));
-                    fs::write(&output_path, &markdown_content).ok();
+    if options.context_header {
+        let mut crumbs = vec![page.category_title.as_str(), page.title.as_str()];
+        crumbs.extend(trail.iter().map(|t| t.as_str()));
+        crumbs.push(&chunk.heading);
+        lines.push(format!("> {}", crumbs.join(" › ")));
+        if !page.summary.is_empty() {
+            lines.push(">".to_string());
+            lines.push(format!("> {}", page.summary));
+        }
+    }
 
-                    continue;
-                }
+    if let Some(overlap) = &chunk.overlap {
+        let label = match options.overlap {
+            ChunkOverlap::Paragraph => "Previously",
+            _ => "Continues from",
+        };
+        if !lines.is_empty() {
+            lines.push(">".to_string());
+        }
+        for (i, line) in overlap.lines().enumerate() {
+            match i {
+                0 => lines.push(format!("> {}: {}", label, line)),
+                _ => lines.push(format!("> {}", line).trim_end().to_string()),
             }
+        }
+    }
 
-            // Not chunked - write as single file
-            let output_path = category_dir.join(format!("{}.md", item_id));
-            fs::write(&output_path, &markdown_content).ok();
+    if lines.is_empty() {
+        return chunk.content.clone();
+    }
+
+    format!("{}\n{}\n{}\n\n{}", CONTEXT_START, lines.join("\n"), CONTEXT_END, chunk.content)
+}
+
+/// Renders markdown inline content as plain text, keeping code spans in backticks.
+fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        match event {
+            Event::Text(t) => text.push_str(&t),
+            Event::Code(c) => {
+                text.push('`');
+                text.push_str(&c);
+                text.push('`');
+            }
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            // Keep adjacent table cells, list items and paragraphs apart
+            Event::End(TagEnd::TableCell | TagEnd::Item | TagEnd::Paragraph) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Summaries
+// ============================================================================
+
+/// Summaries are capped at this many tokens
+const SUMMARY_TOKEN_BUDGET: usize = 60;
+/// The lead sentence is only used if it appears within this many paragraphs
+const SUMMARY_LEAD_PARAGRAPHS: usize = 2;
+/// Sentences shorter than this many words are not considered informative
+const MIN_SUMMARY_WORDS: usize = 6;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "also", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "but", "by", "can", "could", "do", "does", "don",
+    "each", "every", "following", "for", "from", "has", "have", "here", "how", "if", "in",
+    "into", "is", "it", "its", "just", "like", "make", "may", "more", "most", "must",
+    "need", "no", "not", "of", "on", "one", "only", "or", "other", "our", "same", "see",
+    "should", "so", "such", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "to", "too", "use", "used", "uses", "using", "very",
+    "want", "was", "we", "were", "what", "when", "where", "which", "while", "who", "will",
+    "with", "won", "would", "you", "your",
+];
+
+/// Builds a deterministic extractive summary of a page or chunk.
+///
+/// Uses the first informative sentence (and the rest of its paragraph, within the
+/// token budget) when it appears near the top. Otherwise every sentence and list item
+/// is ranked by how many of the section's frequent terms it contains, and the best
+/// ones are kept in document order. Sections without prose fall back to their headings.
+fn summarize(markdown: &str) -> String {
+    let blocks = parse_blocks(markdown);
+    let paragraphs: Vec<Vec<String>> = blocks
+        .iter()
+        .filter(|b| b.kind == BlockKind::Paragraph)
+        .map(|b| split_sentences(&plain_text(&markdown[b.start..b.end])))
+        .collect();
+
+    for paragraph in paragraphs.iter().take(SUMMARY_LEAD_PARAGRAPHS) {
+        if let Some(i) = paragraph.iter().position(|s| is_informative(s)) {
+            let lead: Vec<String> =
+                paragraph[i..].iter().take_while(|s| !s.ends_with(':')).cloned().collect();
+            return fit_summary_budget(&lead);
+        }
+    }
+
+    let mut candidates: Vec<String> = Vec::new();
+    for block in &blocks {
+        let text = &markdown[block.start..block.end];
+        match block.kind {
+            BlockKind::Paragraph => candidates.extend(split_sentences(&plain_text(text))),
+            BlockKind::List => candidates.extend(list_items(text)),
+            _ => {}
+        }
+    }
+    candidates.retain(|s| is_informative(s));
+
+    if candidates.is_empty() {
+        if let Some(sentence) = first_prose(markdown, &blocks) {
+            return fit_summary_budget(&[sentence]);
+        }
+        let headings: Vec<&str> = blocks
+            .iter()
+            .filter_map(|b| match &b.kind {
+                BlockKind::Heading { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let declaration = blocks
+            .iter()
+            .find(|b| b.kind == BlockKind::CodeBlock)
+            .and_then(|b| code_declaration(&markdown[b.start..b.end]));
+        let summary = match (declaration, headings.first()) {
+            (Some(line), Some(heading)) => format!("{}: `{}`", heading, line),
+            (Some(line), None) => format!("`{}`", line),
+            (None, _) => headings.join("; "),
+        };
+        return fit_summary_budget(&[summary]);
+    }
+
+    // Rank sentences by the section-wide frequency of the terms they contain
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for candidate in &candidates {
+        for word in content_words(candidate) {
+            *frequencies.entry(word).or_default() += 1;
+        }
+    }
+    let score = |sentence: &str| -> f64 {
+        let words: HashSet<String> = content_words(sentence).into_iter().collect();
+        let total: usize = words.iter().map(|w| frequencies[w]).sum();
+        total as f64 / (words.len().max(1) as f64).sqrt()
+    };
+
+    let mut ranked: Vec<(usize, f64)> =
+        candidates.iter().enumerate().map(|(i, s)| (i, score(s))).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut chosen: Vec<usize> = Vec::new();
+    for (i, _) in ranked {
+        chosen.push(i);
+        let text = chosen.iter().map(|&c| candidates[c].as_str()).collect::<Vec<_>>().join(" ");
+        if count_tokens(&text) > SUMMARY_TOKEN_BUDGET {
+            if chosen.len() > 1 {
+                chosen.pop();
+            }
+            break;
+        }
+    }
+    chosen.sort_unstable();
+    let sentences: Vec<String> = chosen.into_iter().map(|i| candidates[i].clone()).collect();
+    fit_summary_budget(&sentences)
+}
+
+/// Joins sentences until the token budget is reached. The first sentence is always
+/// kept, truncated at a word boundary if it alone exceeds the budget.
+fn fit_summary_budget(sentences: &[String]) -> String {
+    let mut summary = String::new();
+    for sentence in sentences {
+        let candidate = match summary.is_empty() {
+            true => sentence.clone(),
+            false => format!("{} {}", summary, sentence),
+        };
+        if count_tokens(&candidate) > SUMMARY_TOKEN_BUDGET {
+            break;
+        }
+        summary = candidate;
+    }
+
+    if summary.is_empty() {
+        if let Some(first) = sentences.first() {
+            let mut words: Vec<&str> = Vec::new();
+            for word in first.split_whitespace() {
+                words.push(word);
+                if count_tokens(&words.join(" ")) >= SUMMARY_TOKEN_BUDGET {
+                    words.pop();
+                    break;
+                }
+            }
+            summary = format!("{}…", words.join(" "));
+        }
+    }
+
+    summary
+}
+
+/// Splits plain text into sentences, ignoring periods in common abbreviations.
+fn split_sentences(text: &str) -> Vec<String> {
+    let boundary = Regex::new(r"[.!?]\s+").unwrap();
+    let mut sentences = Vec::new();
+    let mut start = 0;
+
+    for m in boundary.find_iter(text) {
+        let before = &text[start..m.start()];
+        let last_word = before.rsplit(' ').next().unwrap_or("").to_lowercase();
+        let next = text[m.end()..].chars().next();
+        let abbreviation = matches!(last_word.as_str(), "e.g" | "i.e" | "etc" | "vs" | "cf");
+        let starts_sentence =
+            next.is_some_and(|c| c.is_uppercase() || c.is_ascii_digit() || c == '`');
+        if !abbreviation && starts_sentence {
+            sentences.push(text[start..m.start() + 1].trim().to_string());
+            start = m.end();
+        }
+    }
+    if start < text.len() && !text[start..].trim().is_empty() {
+        sentences.push(text[start..].trim().to_string());
+    }
+    sentences
+}
+
+/// The first sentence or list item of a section without informative prose, such as
+/// one that opens with a code sample. A lead-in ending in a colon is completed by the
+/// first item of the list that follows it, or ended with a period before code.
+fn first_prose(markdown: &str, blocks: &[Block]) -> Option<String> {
+    let text = |block: &Block| &markdown[block.start..block.end];
+    for (i, block) in blocks.iter().enumerate() {
+        let first = match block.kind {
+            BlockKind::Paragraph => split_sentences(&plain_text(text(block))).into_iter().next(),
+            BlockKind::List => list_items(text(block)).into_iter().next(),
+            _ => None,
+        };
+        // Skip captions such as "Rust" or "**Generates:**" above a code sample
+        let Some(sentence) = first.filter(|s| {
+            s.split_whitespace().count() > 1 && s.ends_with(['.', '!', '?', ':'])
+        }) else {
+            continue;
+        };
+        let Some(lead_in) = sentence.strip_suffix(':') else {
+            return Some(sentence);
+        };
+        let item = blocks
+            .get(i + 1)
+            .filter(|b| b.kind == BlockKind::List)
+            .and_then(|b| list_items(text(b)).into_iter().next());
+        return Some(match item {
+            Some(item) => format!("{}: {}", lead_in, item),
+            None => format!("{}.", lead_in),
+        });
+    }
+    None
+}
+
+/// The declaration a code block opens with, e.g. `struct DeriveInput`, used to
+/// describe sections that contain only a type or function definition.
+fn code_declaration(code_block: &str) -> Option<String> {
+    let declaration_re = Regex::new(concat!(
+        r"^(?:pub\s+|export\s+|declare\s+|abstract\s+)*",
+        r"(?:struct|enum|trait|type|interface|class|function|fn|const|impl)\s"
+    ))
+    .unwrap();
+    code_block
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("```") && !line.starts_with("~~~"))
+        .find(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with("#["))
+        .filter(|line| declaration_re.is_match(line))
+        .map(|line| line.trim_end_matches(|c: char| c == '{' || c.is_whitespace()).to_string())
+}
+
+/// Returns the plain text of each item in a markdown list block, terminated so that
+/// items read as sentences when joined.
+fn list_items(markdown: &str) -> Vec<String> {
+    let item_re = Regex::new(r"(?m)^\s*(?:[-*+]|\d+[.)])\s+").unwrap();
+    item_re
+        .split(markdown)
+        .map(plain_text)
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.ends_with(['.', '!', '?']) {
+            true => item,
+            false => format!("{}.", item),
+        })
+        .collect()
+}
+
+/// A sentence is informative if it is long enough and is not just a lead-in to a
+/// list or code sample.
+fn is_informative(sentence: &str) -> bool {
+    sentence.split_whitespace().count() >= MIN_SUMMARY_WORDS && !sentence.ends_with(':')
+}
+
+fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() > 2 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+// ============================================================================
+// Keywords
+// ============================================================================
+
+/// Merges the configured use cases and frontmatter keywords of a page, dropping
+/// duplicates (case-insensitively, and after stemming when enabled).
+fn configured_keywords(use_cases: &str, markdown: &str, stem: bool) -> Vec<Keyword> {
+    let manual = use_cases.split(',').map(|term| (term, KeywordSource::Manual));
+    let frontmatter = frontmatter_keywords(markdown);
+    let frontmatter = frontmatter.iter().map(|term| (term.as_str(), KeywordSource::Frontmatter));
+
+    let mut seen = HashSet::new();
+    manual
+        .chain(frontmatter)
+        .map(|(term, source)| (term.trim(), source))
+        .filter(|(term, _)| !term.is_empty() && seen.insert(keyword_key(term, stem)))
+        .map(|(term, source)| Keyword { term: term.to_string(), source })
+        .collect()
+}
+
+/// Reads `keywords`, `tags` or `use_cases` from a YAML frontmatter block, written
+/// either inline (`keywords: a, b` or `[a, b]`) or as a block list.
+fn frontmatter_keywords(markdown: &str) -> Vec<String> {
+    let blocks = parse_blocks(markdown);
+    let Some(metadata) = blocks.iter().find(|b| b.kind == BlockKind::Metadata) else {
+        return Vec::new();
+    };
+
+    let clean = |value: &str| value.trim().trim_matches(['"', '\'']).to_string();
+    let mut keywords = Vec::new();
+    let mut in_list = false;
+    for line in markdown[metadata.start..metadata.end].lines() {
+        if line.trim() == "---" {
+            continue;
+        }
+        let indented = line.starts_with([' ', '\t', '-']);
+        match line.split_once(':') {
+            Some((key, value)) if !indented => {
+                in_list = matches!(key.trim(), "keywords" | "tags" | "use_cases");
+                if in_list {
+                    let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+                    keywords.extend(value.split(',').map(clean));
+                }
+            }
+            _ if in_list => {
+                if let Some(item) = line.trim().strip_prefix('-') {
+                    keywords.push(clean(item));
+                }
+            }
+            _ => {}
+        }
+    }
+    keywords.retain(|k| !k.is_empty());
+    keywords
+}
+
+/// Tops up every section's keywords with its highest scoring TF-IDF terms and fills
+/// in `use_cases`.
+///
+/// Document frequencies are counted over the sections that hold their own content
+/// (standalone pages and leaf chunks), so a page and its chunks are not counted twice.
+fn assign_keywords(sections: &mut [DocSection], stem: bool) {
+    let mut surface_forms: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let frequencies: Vec<HashMap<String, f64>> = sections
+        .iter()
+        .map(|s| term_frequencies(&s.text, stem, &mut surface_forms))
+        .collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    let mut documents = 0;
+    for (section, terms) in sections.iter().zip(&frequencies) {
+        if section.is_chunked.is_none() {
+            documents += 1;
+            for term in terms.keys() {
+                *document_frequency.entry(term.as_str()).or_default() += 1;
+            }
+        }
+    }
+    let idf = |term: &str| {
+        let df = document_frequency.get(term).copied().unwrap_or(0);
+        ((documents as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0
+    };
+
+    for (section, terms) in sections.iter_mut().zip(&frequencies) {
+        let mut scored: Vec<(&str, f64)> =
+            terms.iter().map(|(term, tf)| (term.as_str(), (1.0 + tf).ln() * idf(term))).collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let mut seen: HashSet<String> =
+            section.keywords.iter().map(|k| keyword_key(&k.term, stem)).collect();
+        let extracted: Vec<Keyword> = scored
+            .into_iter()
+            .filter(|(term, _)| seen.insert(term.to_string()))
+            .take(EXTRACTED_KEYWORDS)
+            .map(|(term, _)| Keyword {
+                term: preferred_surface_form(&surface_forms[term]),
+                source: KeywordSource::Extracted,
+            })
+            .collect();
+        section.keywords.extend(extracted);
+
+        let terms: Vec<&str> = section.keywords.iter().map(|k| k.term.as_str()).collect();
+        section.use_cases = terms.join(", ");
+    }
+}
+
+/// Counts the terms of a section's prose, headings and inline code. Code blocks, HTML
+/// and frontmatter are skipped. Heading terms count double, and the parts of a split
+/// identifier count half.
+fn term_frequencies(
+    markdown: &str,
+    stem: bool,
+    surface_forms: &mut HashMap<String, HashMap<String, usize>>,
+) -> HashMap<String, f64> {
+    let token_re = Regex::new(r"@?[A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*").unwrap();
+    let mut frequencies: HashMap<String, f64> = HashMap::new();
+    let mut add = |key: String, surface: &str, weight: f64| {
+        *frequencies.entry(key.clone()).or_default() += weight;
+        *surface_forms.entry(key).or_default().entry(surface.to_string()).or_default() += 1;
+    };
+
+    for block in parse_blocks(markdown) {
+        let weight = match block.kind {
+            BlockKind::CodeBlock | BlockKind::Html | BlockKind::Metadata => continue,
+            BlockKind::Heading { .. } => 2.0,
+            _ => 1.0,
+        };
+        let text = plain_text(&markdown[block.start..block.end]);
+
+        for token in token_re.find_iter(&text).map(|m| m.as_str()) {
+            if is_identifier(token) {
+                add(token.to_lowercase(), token, weight);
+                for part in identifier_parts(token) {
+                    if is_keyword_word(&part) {
+                        add(keyword_key(&part, stem), &part, weight / 2.0);
+                    }
+                }
+            } else {
+                let word = token.to_lowercase();
+                if is_keyword_word(&word) {
+                    add(keyword_key(&word, stem), &word, weight);
+                }
+            }
+        }
+    }
+
+    frequencies
+}
+
+/// Decorators (`@derive`), paths (`serde::Serialize`), snake_case and camelCase names
+/// are kept whole; plain words are lowercased and optionally stemmed.
+fn is_identifier(token: &str) -> bool {
+    let camel = token
+        .as_bytes()
+        .windows(2)
+        .any(|w| w[0].is_ascii_lowercase() && w[1].is_ascii_uppercase());
+    token.starts_with('@') || token.contains('_') || token.contains("::") || camel
+}
+
+/// Splits `fromJSON`, `ts_macro_derive` or `@serde` into lowercase words.
+fn identifier_parts(token: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = token.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            parts.push(std::mem::take(&mut current));
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|p| p.is_lowercase()
+                || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase())));
+        if boundary {
+            parts.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    parts.push(current);
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+fn is_keyword_word(word: &str) -> bool {
+    word.len() > 2
+        && word.starts_with(|c: char| c.is_alphabetic())
+        && !STOP_WORDS.contains(&word)
+}
+
+/// Normalizes a keyword for comparison: lowercase, with each word stemmed if enabled.
+fn keyword_key(term: &str, stem: bool) -> String {
+    let term = term.to_lowercase();
+    if !stem || term.starts_with('@') {
+        return term;
+    }
+    term.split(' ').map(stem_word).collect::<Vec<_>>().join(" ")
+}
+
+/// Light suffix stripping so `macros`/`macro` and `caching`/`cache` share a stem.
+/// Identifiers are left untouched.
+fn stem_word(word: &str) -> String {
+    if !word.chars().all(|c| c.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    if let Some(base) = word.strip_suffix("ies").filter(|b| b.len() >= 3) {
+        return format!("{}y", base);
+    }
+    for suffix in ["ing", "ed", "es", "e", "s"] {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() >= 3 && !(suffix == "s" && base.ends_with('s')) {
+                return base.to_string();
+            }
+        }
+    }
+    word.to_string()
+}
+
+/// The most common spelling of a term across the corpus, ties broken alphabetically.
+fn preferred_surface_form(forms: &HashMap<String, usize>) -> String {
+    forms
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(form, _)| form.clone())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Search Index
+// ============================================================================
+
+/// BM25 term frequency saturation
+const BM25_K1: f64 = 1.2;
+/// BM25 length normalization
+const BM25_B: f64 = 0.75;
+/// Indexed fields, in posting order, and their BM25F weights
+const INDEX_FIELDS: [(&str, f64); 4] =
+    [("title", 3.0), ("headings", 2.0), ("keywords", 2.0), ("body", 1.0)];
+
+/// Precomputed BM25F inverted index over every section, written to `search-index.json`.
+///
+/// Format (version 1):
+/// - `fields`, `weights`: indexed fields in posting order and their weights
+/// - `k1`, `b`: BM25 parameters
+/// - `stop_words`: words dropped by the tokenizer
+/// - `docs`: section ids; a document number is an index into this array
+/// - `lengths`: for each document, the number of terms in each field
+/// - `avg_lengths`: mean number of terms in each field
+/// - `postings`: term to a flat array of `doc, tf_title, tf_headings, tf_keywords, tf_body`
+///   groups, sorted by document number
+///
+/// Terms come from `index_terms`, which queries must be tokenized with as well.
+#[derive(Debug, Serialize)]
+struct SearchIndex {
+    version: u32,
+    fields: Vec<&'static str>,
+    weights: Vec<f64>,
+    k1: f64,
+    b: f64,
+    stop_words: &'static [&'static str],
+    docs: Vec<String>,
+    lengths: Vec<Vec<usize>>,
+    avg_lengths: Vec<f64>,
+    postings: BTreeMap<String, Vec<usize>>,
+}
+
+fn build_search_index(sections: &[DocSection]) -> SearchIndex {
+    let mut docs = Vec::new();
+    let mut lengths = Vec::new();
+    let mut postings: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    for (doc, section) in sections.iter().enumerate() {
+        let (headings, body) = split_headings(&section.text);
+        let keywords: Vec<&str> = section.keywords.iter().map(|k| k.term.as_str()).collect();
+        let fields = [
+            index_terms(&section.title),
+            index_terms(&headings),
+            index_terms(&keywords.join(" ")),
+            index_terms(&body),
+        ];
+
+        let mut frequencies: BTreeMap<&str, [usize; 4]> = BTreeMap::new();
+        for (field, terms) in fields.iter().enumerate() {
+            for term in terms {
+                frequencies.entry(term.as_str()).or_default()[field] += 1;
+            }
+        }
+        for (term, tf) in frequencies {
+            let posting = postings.entry(term.to_string()).or_default();
+            posting.push(doc);
+            posting.extend(tf);
+        }
+
+        docs.push(section.id.clone());
+        lengths.push(fields.iter().map(Vec::len).collect::<Vec<_>>());
+    }
+
+    let avg_lengths = (0..INDEX_FIELDS.len())
+        .map(|field| {
+            let total: usize = lengths.iter().map(|l| l[field]).sum();
+            total as f64 / docs.len().max(1) as f64
+        })
+        .collect();
+
+    SearchIndex {
+        version: 1,
+        fields: INDEX_FIELDS.iter().map(|(name, _)| *name).collect(),
+        weights: INDEX_FIELDS.iter().map(|(_, weight)| *weight).collect(),
+        k1: BM25_K1,
+        b: BM25_B,
+        stop_words: STOP_WORDS,
+        docs,
+        lengths,
+        avg_lengths,
+        postings,
+    }
+}
+
+/// Returns the plain text of a section's headings and of everything else, code
+/// included. Frontmatter and HTML blocks are dropped.
+fn split_headings(markdown: &str) -> (String, String) {
+    let mut headings = Vec::new();
+    let mut body = Vec::new();
+    for block in parse_blocks(markdown) {
+        let text = &markdown[block.start..block.end];
+        match block.kind {
+            BlockKind::Heading { text, .. } => headings.push(text),
+            BlockKind::Metadata | BlockKind::Html => {}
+            _ => body.push(plain_text(text)),
+        }
+    }
+    (headings.join("\n"), body.join("\n"))
+}
+
+/// Tokenizes text for the search index: alphanumeric runs are lowercased and stemmed,
+/// and identifiers also contribute their parts, so `serializeWithContext` matches both
+/// itself and `serialize`, `context`. Stop words and single characters are dropped.
+fn index_terms(text: &str) -> Vec<String> {
+    let token_re = Regex::new(r"[A-Za-z0-9_]+").unwrap();
+    let mut terms = Vec::new();
+    let mut push = |word: &str| {
+        if word.len() > 1 && !STOP_WORDS.contains(&word) {
+            terms.push(stem_word(word));
+        }
+    };
+
+    for token in token_re.find_iter(text).map(|m| m.as_str()) {
+        push(&token.to_lowercase());
+        let parts = identifier_parts(token);
+        if parts.len() > 1 {
+            parts.iter().for_each(|part| push(part));
+        }
+    }
+    terms
+}
+
+// ============================================================================
+// Vectors
+// ============================================================================
+
+/// Number of hashed feature buckets per vector
+const VECTOR_DIMENSIONS: usize = 1024;
+/// Terms longer than this also contribute a prefix feature of this many characters,
+/// so `compare`, `comparison` and `comparing` overlap
+const PREFIX_FEATURE_LENGTH: usize = 5;
+
+/// Sparse, L2-normalized hashed TF-IDF vectors, written to `vectors.json`.
+///
+/// Format (version 1):
+/// - `dimensions`: number of buckets; a feature's bucket is its 32-bit FNV-1a hash
+///   (over UTF-8 bytes) modulo `dimensions`
+/// - `prefix_length`: length of the `~prefix` feature added for longer terms
+/// - `idf`: inverse document frequency of each bucket, used to weight queries
+/// - `docs`: section ids, parallel to `vectors`
+/// - `vectors`: non-zero `buckets` (ascending) and their `weights`
+///
+/// Features are the `index_terms` of the title, keywords (both counted twice), headings
+/// and body, plus a `~` prefix feature for terms longer than `prefix_length`. Weights
+/// are `(1 + ln tf) * idf`, normalized to unit length.
+#[derive(Debug, Serialize)]
+struct SectionVectors {
+    version: u32,
+    dimensions: usize,
+    prefix_length: usize,
+    idf: Vec<f64>,
+    docs: Vec<String>,
+    vectors: Vec<SparseVector>,
+}
+
+#[derive(Debug, Serialize)]
+struct SparseVector {
+    buckets: Vec<usize>,
+    weights: Vec<f64>,
+}
+
+fn build_section_vectors(sections: &[DocSection]) -> SectionVectors {
+    let counts: Vec<BTreeMap<usize, usize>> = sections
+        .iter()
+        .map(|section| {
+            let (headings, body) = split_headings(&section.text);
+            let keywords: Vec<&str> = section.keywords.iter().map(|k| k.term.as_str()).collect();
+            let keywords = keywords.join(" ");
+            let text = [&section.title, &section.title, &keywords, &keywords, &headings, &body];
+
+            let mut counts = BTreeMap::new();
+            for feature in text.iter().flat_map(|t| vector_features(t)) {
+                *counts.entry(feature_bucket(&feature)).or_default() += 1;
+            }
+            counts
+        })
+        .collect();
+
+    let mut document_frequency = vec![0usize; VECTOR_DIMENSIONS];
+    for bucket in counts.iter().flat_map(|c| c.keys()) {
+        document_frequency[*bucket] += 1;
+    }
+    let documents = sections.len() as f64;
+    let idf: Vec<f64> = document_frequency
+        .iter()
+        .map(|&df| round_weight(((documents + 1.0) / (df as f64 + 1.0)).ln() + 1.0))
+        .collect();
+
+    let vectors = counts
+        .iter()
+        .map(|counts| {
+            let raw: Vec<(usize, f64)> = counts
+                .iter()
+                .map(|(&bucket, &tf)| (bucket, (1.0 + (tf as f64).ln()) * idf[bucket]))
+                .collect();
+            let norm = raw.iter().map(|(_, w)| w * w).sum::<f64>().sqrt().max(f64::EPSILON);
+            SparseVector {
+                buckets: raw.iter().map(|(bucket, _)| *bucket).collect(),
+                weights: raw.iter().map(|(_, w)| round_weight(w / norm)).collect(),
+            }
+        })
+        .collect();
+
+    SectionVectors {
+        version: 1,
+        dimensions: VECTOR_DIMENSIONS,
+        prefix_length: PREFIX_FEATURE_LENGTH,
+        idf,
+        docs: sections.iter().map(|s| s.id.clone()).collect(),
+        vectors,
+    }
+}
+
+fn vector_features(text: &str) -> Vec<String> {
+    let mut features = Vec::new();
+    for term in index_terms(text) {
+        if term.is_ascii() && term.len() > PREFIX_FEATURE_LENGTH {
+            features.push(format!("~{}", &term[..PREFIX_FEATURE_LENGTH]));
+        }
+        features.push(term);
+    }
+    features
+}
+
+/// 32-bit FNV-1a, chosen because it is trivial to reproduce in the server.
+fn feature_bucket(feature: &str) -> usize {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in feature.bytes() {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash as usize % VECTOR_DIMENSIONS
+}
+
+/// Rounds to four decimal places to keep the JSON compact.
+fn round_weight(weight: f64) -> f64 {
+    (weight * 10_000.0).round() / 10_000.0
+}
+
+// ============================================================================
+// Aliases
+// ============================================================================
+
+/// Alias dictionary written to `aliases.json`: lowercase spelling to the section ids
+/// it refers to (most specific first) and where the alias came from.
+#[derive(Debug, Serialize)]
+struct AliasDictionary {
+    version: u32,
+    aliases: BTreeMap<String, AliasEntry>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct AliasEntry {
+    ids: Vec<String>,
+    sources: Vec<AliasSource>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum AliasSource {
+    /// Page title or a chunk heading that is unique across the docs
+    Title,
+    /// Href path or its last segment
+    Href,
+    /// Identifier from a section's keywords or headings
+    Identifier,
+    /// From `get_synonyms_map`
+    Synonym,
+    /// From `get_renamed_ids_map`
+    Renamed,
+}
+
+impl AliasDictionary {
+    /// Adds the alias in every spelling produced by `alias_spellings`.
+    fn add(&mut self, alias: &str, id: &str, source: AliasSource) {
+        for spelling in alias_spellings(alias) {
+            self.add_exact(&spelling, id, source);
+        }
+    }
+
+    fn add_exact(&mut self, alias: &str, id: &str, source: AliasSource) {
+        let entry = self.aliases.entry(alias.to_lowercase()).or_default();
+        if !entry.ids.iter().any(|existing| existing == id) {
+            entry.ids.push(id.to_string());
+        }
+        if !entry.sources.contains(&source) {
+            entry.sources.push(source);
+        }
+    }
+}
+
+fn build_alias_dictionary(
+    navigation: &[NavSection],
+    page_ids: &HashMap<String, String>,
+    sections: &[DocSection],
+) -> AliasDictionary {
+    let mut dictionary = AliasDictionary { version: 1, aliases: BTreeMap::new() };
+
+    for item in navigation.iter().flat_map(|s| &s.items) {
+        let id = &page_ids[&item.href];
+        let path = item.href.strip_prefix("/docs/").unwrap_or(&item.href).trim_matches('/');
+        let leaf = path.rsplit('/').next().unwrap_or(path);
+
+        dictionary.add(&item.title, id, AliasSource::Title);
+        dictionary.add(leaf, id, AliasSource::Href);
+        dictionary.add_exact(path, id, AliasSource::Href);
+        dictionary.add_exact(&item.href, id, AliasSource::Href);
+
+        // Macro pages are also referred to by their decorator form
+        if href_to_category(&item.href) == "builtin-macros" && path.contains('/') {
+            let name = item.title.to_lowercase();
+            dictionary.add_exact(&format!("@derive({})", name), id, AliasSource::Title);
+            dictionary.add_exact(&format!("derive({})", name), id, AliasSource::Title);
+        }
+    }
+
+    // Chunk headings, unless the same heading appears in several places
+    let chunk_heading = |section: &DocSection| {
+        let title = section.title.as_str();
+        title.split_once(": ").map_or(title, |(_, heading)| heading).to_string()
+    };
+    let mut heading_counts: HashMap<String, usize> = HashMap::new();
+    for section in sections.iter().filter(|s| s.parent_id.is_some()) {
+        *heading_counts.entry(chunk_heading(section).to_lowercase()).or_default() += 1;
+    }
+    for section in sections.iter().filter(|s| s.parent_id.is_some()) {
+        let heading = chunk_heading(section);
+        if heading_counts[&heading.to_lowercase()] == 1 {
+            dictionary.add(&heading, &section.id, AliasSource::Title);
+        }
+    }
+
+    let identifier_re =
+        Regex::new(r"@?[A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*!?").unwrap();
+    for section in sections {
+        let mut heading_identifiers = Vec::new();
+        for block in parse_blocks(&section.text) {
+            if let BlockKind::Heading { text, .. } = block.kind {
+                let found = identifier_re.find_iter(&text).map(|m| m.as_str().to_string());
+                heading_identifiers.extend(found);
+            }
+        }
+        let keywords = section.keywords.iter().map(|k| k.term.clone());
+
+        for term in keywords.chain(heading_identifiers) {
+            if is_identifier(term.trim_end_matches('!')) {
+                dictionary.add(&term, &section.id, AliasSource::Identifier);
+            }
+        }
+    }
+
+    for (href, synonyms) in get_synonyms_map() {
+        let Some(id) = page_ids.get(href) else {
+            eprintln!("Warning: Synonyms configured for unknown page {}", href);
+            continue;
+        };
+        for synonym in synonyms.split(',') {
+            dictionary.add(synonym, id, AliasSource::Synonym);
+        }
+    }
+
+    for (old_id, new_id) in get_renamed_ids_map() {
+        if sections.iter().any(|s| s.id == new_id) {
+            dictionary.add_exact(old_id, new_id, AliasSource::Renamed);
+        } else {
+            eprintln!("Warning: Renamed id {} points at unknown section {}", old_id, new_id);
+        }
+    }
+
+    dictionary
+}
+
+/// Returns the lowercase spellings of a term: as written, plus its words joined as
+/// kebab-case, snake_case, spaced and concatenated (which covers PascalCase and
+/// camelCase once lowercased). `@serde` also yields `serde`.
+fn alias_spellings(term: &str) -> Vec<String> {
+    let term = term.trim();
+    let words = identifier_parts(term);
+    let mut spellings = vec![term.to_lowercase()];
+    if words.len() > 1 {
+        spellings.extend(["-", "_", " ", ""].iter().map(|sep| words.join(sep)));
+    } else {
+        spellings.extend(words);
+    }
+
+    let mut seen = HashSet::new();
+    spellings.retain(|s| {
+        s.len() > 1 && !STOP_WORDS.contains(&s.as_str()) && seen.insert(s.clone())
+    });
+    spellings
+}
+
+// ============================================================================
+// Examples
+// ============================================================================
+
+/// Paragraphs of at most this many words directly above a code block are read as its
+/// caption (`Bash`, `vite.config.ts`, `Before (Your Code)`) rather than as prose
+const MAX_LABEL_WORDS: usize = 4;
+
+/// A fenced code block, written to `examples.json`.
+#[derive(Debug, Serialize)]
+struct CodeExample {
+    /// `<section id>#example-<n>`, numbered within the section
+    id: String,
+    /// Leaf chunk (or unchunked page) containing the block
+    section_id: String,
+    language: String,
+    kind: ExampleKind,
+    /// File name from the block's caption, e.g. `vite.config.ts`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_name: Option<String>,
+    /// Nearest heading above the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    heading: Option<String>,
+    /// Last sentence of the prose introducing the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    /// Id of the example pair this block belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pair_id: Option<String>,
+    code: String,
+    source_map: SourceMap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ExampleKind {
+    /// Code using macros, before expansion
+    Input,
+    /// Code generated by expanding macros
+    Expanded,
+    /// Configuration file contents
+    Config,
+    /// Shell commands
+    Shell,
+    /// Any other code
+    Code,
+}
+
+/// A fenced code block and the text around it, before classification.
+struct FencedBlock {
+    start: usize,
+    end: usize,
+    info: String,
+    code: String,
+    heading: Option<String>,
+    label: Option<String>,
+    context: Option<String>,
+}
+
+/// Catalogues the fenced code blocks of a page, attributing each to the leaf chunk
+/// that contains it (or the page itself when it is not chunked).
+fn page_examples(page_id: &str, source: &PageSource, chunks: &[Chunk]) -> Vec<CodeExample> {
+    let leaves = page_leaves(page_id, source.markdown.len(), chunks);
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    fenced_blocks(&source.markdown)
+        .into_iter()
+        .map(|block| {
+            let section_id = leaf_at(&leaves, page_id, block.start);
+            let n = counts.entry(section_id.clone()).or_default();
+            *n += 1;
+
+            let language = example_language(&block.info, block.label.as_deref());
+            let file_name = block
+                .label
+                .as_ref()
+                .filter(|l| l.contains('.') && !l.contains(' '))
+                .cloned();
+            let kind = example_kind(&block, &language, file_name.as_deref());
+
+            CodeExample {
+                id: format!("{}#example-{}", section_id, n),
+                section_id,
+                language,
+                kind,
+                file_name,
+                heading: block.heading,
+                context: block.context,
+                pair_id: None,
+                code: block.code,
+                source_map: source_map(source, &[], block.start, block.end),
+            }
+        })
+        .collect()
+}
+
+/// Lists `(id, start, end)` of every leaf chunk, or the whole page when it is not
+/// chunked.
+fn page_leaves(page_id: &str, len: usize, chunks: &[Chunk]) -> Vec<(String, usize, usize)> {
+    let mut leaves = Vec::new();
+    collect_leaves(chunks, page_id, &mut leaves);
+    if leaves.is_empty() {
+        leaves.push((page_id.to_string(), 0, len));
+    }
+    leaves
+}
+
+/// Returns the id of the leaf containing `offset`.
+fn leaf_at(leaves: &[(String, usize, usize)], page_id: &str, offset: usize) -> String {
+    leaves
+        .iter()
+        .find(|(_, start, end)| offset >= *start && offset < *end)
+        .map_or(page_id, |(id, _, _)| id.as_str())
+        .to_string()
+}
+
+/// Collects `(id, start, end)` of every leaf chunk.
+fn collect_leaves(chunks: &[Chunk], page_id: &str, leaves: &mut Vec<(String, usize, usize)>) {
+    for chunk in chunks {
+        if chunk.children.is_empty() {
+            leaves.push((format!("{}/{}", page_id, chunk.slug), chunk.start, chunk.end));
+        } else {
+            collect_leaves(&chunk.children, page_id, leaves);
+        }
+    }
+}
+
+/// Walks every fenced code block of a page, including those nested in lists and
+/// block quotes, recording the nearest heading and the paragraphs just above it.
+fn fenced_blocks(markdown: &str) -> Vec<FencedBlock> {
+    let mut blocks = Vec::new();
+    let mut heading: Option<String> = None;
+    // Paragraphs since the last heading, with their end offsets
+    let mut paragraphs: Vec<(usize, String)> = Vec::new();
+    let mut fence: Option<(usize, String, String)> = None;
+
+    for (event, range) in Parser::new_ext(markdown, markdown_options()).into_offset_iter() {
+        match event {
+            Event::End(TagEnd::Heading(_)) => {
+                heading = Some(plain_text(&markdown[range]));
+                paragraphs.clear();
+            }
+            Event::End(TagEnd::Paragraph) => {
+                paragraphs.push((range.end, plain_text(&markdown[range])));
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                fence = Some((range.start, info.to_string(), String::new()));
+            }
+            Event::Text(text) => {
+                if let Some((_, _, code)) = &mut fence {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let Some((start, info, code)) = fence.take() else {
+                    continue;
+                };
+                let adjacent = |end: usize| markdown[end.min(start)..start].trim().is_empty();
+                let label = paragraphs
+                    .last()
+                    .filter(|(end, text)| adjacent(*end) && is_code_label(text))
+                    .map(|(_, text)| text.clone());
+                let prose = paragraphs.iter().rev().find(|(_, text)| !is_code_label(text));
+                let context = prose.and_then(|(_, text)| split_sentences(text).pop());
+
+                blocks.push(FencedBlock {
+                    start,
+                    end: range.end,
+                    info,
+                    code: code.trim_end_matches('\n').to_string(),
+                    heading: heading.clone(),
+                    label,
+                    context,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
+fn is_code_label(text: &str) -> bool {
+    let words = text.split_whitespace().count();
+    words > 0 && words <= MAX_LABEL_WORDS && !text.ends_with(['.', ':', '!', '?'])
+}
+
+/// The language from the fence info string, or else from the caption: a language
+/// name, a package manager, a file extension or a Before/After label.
+fn example_language(info: &str, label: Option<&str>) -> String {
+    if let Some(language) = info.split_whitespace().next() {
+        return language.to_lowercase();
+    }
+    let Some(label) = label.map(str::to_lowercase) else {
+        return "text".to_string();
+    };
+
+    let language = match label.as_str() {
+        "rust" | "typescript" | "javascript" | "bash" | "json" | "text" | "svelte" | "toml"
+        | "yaml" => label.as_str(),
+        "npm" | "pnpm" | "yarn" | "bun" | "shell" | "terminal" => "bash",
+        _ if label.starts_with("before") || label.starts_with("after") => "typescript",
+        _ => match label.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("ts") => "typescript",
+            Some("js" | "mjs" | "cjs") => "javascript",
+            Some("json") => "json",
+            Some("toml") => "toml",
+            Some("yaml" | "yml") => "yaml",
+            Some("rs") => "rust",
+            Some("svelte") => "svelte",
+            _ => "text",
+        },
+    };
+    language.to_string()
+}
+
+/// Classifies a code block. Explicit before/after markers win; otherwise the
+/// language and file name decide shell and config blocks, and TypeScript is an
+/// input when it uses `@derive` or expanded output when its introduction says so.
+fn example_kind(block: &FencedBlock, language: &str, file_name: Option<&str>) -> ExampleKind {
+    let info = block.info.to_lowercase();
+    let label = block.label.as_deref().unwrap_or("").to_lowercase();
+    let marked = |marker: &str| {
+        info.split_whitespace().any(|w| w == marker) || label.starts_with(marker)
+    };
+
+    if marked("before") {
+        return ExampleKind::Input;
+    }
+    if marked("after") {
+        return ExampleKind::Expanded;
+    }
+    if matches!(language, "bash" | "sh" | "shell" | "console" | "zsh" | "powershell") {
+        return ExampleKind::Shell;
+    }
+    let config_file = file_name.is_some_and(|f| f.contains("config") || f.ends_with(".json"));
+    if matches!(language, "json" | "jsonc" | "toml" | "yaml" | "yml") || config_file {
+        return ExampleKind::Config;
+    }
+
+    if matches!(language, "typescript" | "ts" | "javascript" | "js" | "svelte") {
+        if block.code.contains("@derive") {
+            return ExampleKind::Input;
+        }
+        let intro = format!(
+            "{} {}",
+            block.heading.as_deref().unwrap_or(""),
+            block.context.as_deref().unwrap_or("")
+        )
+        .to_lowercase();
+        let cues = ["generates", "generated", "expands to", "expanded", "expansion", "output"];
+        if cues.iter().any(|cue| intro.contains(cue)) {
+            return ExampleKind::Expanded;
+        }
+    }
+
+    ExampleKind::Code
+}
+
+// ============================================================================
+// Example Pairs
+// ============================================================================
+
+/// An input sample linked to the expanded output shown for it, written to
+/// `example-pairs.json` for golden tests and few-shot prompts.
+#[derive(Debug, Serialize)]
+struct ExamplePair {
+    /// `<section id>#pair-<n>`
+    id: String,
+    section_id: String,
+    /// Id of the input example in `examples.json`
+    input: String,
+    /// Id of the expanded output example in `examples.json`
+    output: String,
+    /// Macros named in the input's `@derive(...)`
+    macros: Vec<String>,
+    /// Types declared in the input that the output expands
+    types: Vec<String>,
+    /// Members and functions that only exist in the output, e.g. `User.toString`
+    generated: Vec<String>,
+    /// Whether both samples parsed and the output expands the input's types. Unverified
+    /// pairs are adjacent before/after samples that failed to parse (e.g. placeholders).
+    verified: bool,
+}
+
+/// Names declared by a TypeScript snippet.
+#[derive(Debug, Default)]
+struct TsOutline {
+    /// Classes, interfaces, enums, type aliases and namespaces
+    types: Vec<String>,
+    /// `Type.member` for class, interface and namespace members; plain names for
+    /// top-level functions and variables
+    members: Vec<String>,
+}
+
+/// Links each input example to the expanded example that follows it under the same
+/// heading. A pair is verified when both parse as TypeScript and the output redeclares
+/// one of the input's types or declares functions named after one (`userToString`).
+/// Samples that do not parse are still paired when they are directly adjacent.
+fn pair_examples(examples: &mut [CodeExample]) -> Vec<ExamplePair> {
+    let derive_re = Regex::new(r"@derive\(([^)]*)\)").unwrap();
+    let mut pairs = Vec::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut pending: Option<(usize, Option<TsOutline>)> = None;
+
+    for i in 0..examples.len() {
+        match examples[i].kind {
+            ExampleKind::Input => pending = Some((i, parse_ts_outline(&examples[i].code))),
+            ExampleKind::Expanded => {
+                let Some((input, input_outline)) = pending.take() else {
+                    continue;
+                };
+                let (before, after) = (&examples[input], &examples[i]);
+                if before.section_id != after.section_id || before.heading != after.heading {
+                    continue;
+                }
+
+                let (types, generated) = match (&input_outline, parse_ts_outline(&after.code)) {
+                    (Some(input_outline), Some(output_outline)) => {
+                        expanded_names(input_outline, &output_outline)
+                    }
+                    _ => (Vec::new(), Vec::new()),
+                };
+                let verified = !types.is_empty();
+                if !verified && i != input + 1 {
+                    continue;
+                }
+
+                let macros = derive_re
+                    .captures_iter(&before.code)
+                    .flat_map(|c| c[1].split(',').map(str::trim).map(String::from).collect::<Vec<_>>())
+                    .filter(|m| !m.is_empty())
+                    .collect();
+
+                let section_id = before.section_id.clone();
+                let n = counts.entry(section_id.clone()).or_default();
+                *n += 1;
+                let id = format!("{}#pair-{}", section_id, n);
+                pairs.push(ExamplePair {
+                    id: id.clone(),
+                    section_id,
+                    input: before.id.clone(),
+                    output: after.id.clone(),
+                    macros,
+                    types,
+                    generated,
+                    verified,
+                });
+                examples[input].pair_id = Some(id.clone());
+                examples[i].pair_id = Some(id);
+            }
+            _ => {}
+        }
+    }
+
+    pairs
+}
+
+/// Returns the input types the output expands and the names only the output declares.
+fn expanded_names(input: &TsOutline, output: &TsOutline) -> (Vec<String>, Vec<String>) {
+    let generated: Vec<String> =
+        output.members.iter().filter(|m| !input.members.contains(m)).cloned().collect();
+    let types = input
+        .types
+        .iter()
+        .filter(|t| {
+            let prefix = t.to_lowercase();
+            output.types.contains(t)
+                || generated.iter().any(|g| g.to_lowercase().starts_with(&prefix))
+        })
+        .cloned()
+        .collect();
+    (types, generated)
+}
+
+/// Parses a snippet as TypeScript and lists what it declares, or `None` if it does
+/// not parse cleanly.
+fn parse_ts_outline(code: &str) -> Option<TsOutline> {
+    let allocator = Allocator::default();
+    let parsed = TsParser::new(&allocator, code, SourceType::ts()).parse();
+    if parsed.panicked || !parsed.errors.is_empty() {
+        return None;
+    }
+
+    let mut outline = TsOutline::default();
+    for statement in &parsed.program.body {
+        outline_statement(code, statement, None, &mut outline);
+    }
+    Some(outline)
+}
+
+fn outline_statement(
+    code: &str,
+    statement: &Statement,
+    namespace: Option<&str>,
+    outline: &mut TsOutline,
+) {
+    let declaration = match statement {
+        Statement::ExportNamedDeclaration(export) => export.declaration.as_ref(),
+        _ => statement.as_declaration(),
+    };
+    let Some(declaration) = declaration else {
+        return;
+    };
+    let qualified = |name: &str| match namespace {
+        Some(namespace) => format!("{}.{}", namespace, name),
+        None => name.to_string(),
+    };
+
+    match declaration {
+        Declaration::ClassDeclaration(class) => {
+            let Some(id) = &class.id else { return };
+            outline.types.push(id.name.to_string());
+            for element in &class.body.body {
+                // Computed keys such as `[Symbol.for("...")]` are named by their source
+                let key = match element {
+                    ClassElement::MethodDefinition(method) => &method.key,
+                    ClassElement::PropertyDefinition(property) => &property.key,
+                    _ => continue,
+                };
+                let name = key.static_name().map(|n| n.to_string()).unwrap_or_else(|| {
+                    let span = key.span();
+                    format!("[{}]", &code[span.start as usize..span.end as usize])
+                });
+                outline.members.push(format!("{}.{}", id.name, name));
+            }
+        }
+        Declaration::TSInterfaceDeclaration(interface) => {
+            outline.types.push(interface.id.name.to_string());
+            for signature in &interface.body.body {
+                let key = match signature {
+                    TSSignature::TSPropertySignature(p) => &p.key,
+                    TSSignature::TSMethodSignature(m) => &m.key,
+                    _ => continue,
+                };
+                if let Some(name) = key.static_name() {
+                    outline.members.push(format!("{}.{}", interface.id.name, name));
+                }
+            }
+        }
+        Declaration::TSEnumDeclaration(e) => outline.types.push(e.id.name.to_string()),
+        Declaration::TSTypeAliasDeclaration(alias) => {
+            outline.types.push(alias.id.name.to_string());
+        }
+        Declaration::TSModuleDeclaration(module) => {
+            let TSModuleDeclarationName::Identifier(id) = &module.id else { return };
+            outline.types.push(id.name.to_string());
+            if let Some(TSModuleDeclarationBody::TSModuleBlock(block)) = &module.body {
+                for statement in &block.body {
+                    outline_statement(code, statement, Some(&id.name), outline);
+                }
+            }
+        }
+        Declaration::FunctionDeclaration(function) => {
+            if let Some(id) = &function.id {
+                outline.members.push(qualified(&id.name));
+            }
+        }
+        Declaration::VariableDeclaration(variables) => {
+            for declarator in &variables.declarations {
+                if let Some(name) = declarator.id.get_identifier_name() {
+                    outline.members.push(qualified(&name));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// ============================================================================
+// Macro Catalog
+// ============================================================================
+
+/// A built-in macro, written to `macros.json` so macro lookups work without the
+/// native bindings.
+#[derive(Debug, Serialize)]
+struct MacroInfo {
+    /// Name used in `@derive(...)`, e.g. `Debug`
+    name: String,
+    /// Id of the macro's page
+    section_id: String,
+    description: String,
+    /// Field decorator configuring the macro, e.g. `@serde`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decorator: Option<String>,
+    targets: Vec<MacroTarget>,
+    generated: Vec<GeneratedMember>,
+    options: Vec<MacroOption>,
+    /// Ids of the example pairs in `example-pairs.json` shown on the macro's page
+    example_pairs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum MacroTarget {
+    Class,
+    Interface,
+    Enum,
+    #[serde(rename = "type alias")]
+    TypeAlias,
+}
+
+/// A method or function a macro generates.
+#[derive(Debug, Clone, Serialize)]
+struct GeneratedMember {
+    /// Name as documented; standalone functions keep their placeholder prefix,
+    /// e.g. `classNameClone`
+    name: String,
+    kind: MemberKind,
+    signature: String,
+    targets: Vec<MacroTarget>,
+    section_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum MemberKind {
+    Method,
+    StaticMethod,
+    Function,
+}
+
+/// A decorator argument accepted by a macro.
+#[derive(Debug, Serialize)]
+struct MacroOption {
+    name: String,
+    /// Other option names documented on the same line, e.g. `skipSerializing`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    /// Forms as written in the docs, e.g. `rename = "jsonKey"`
+    syntax: Vec<String>,
+    level: OptionLevel,
+    description: String,
+    section_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum OptionLevel {
+    Field,
+    Container,
+}
+
+/// Macro pages and rows of the overview table, gathered while pages are processed.
+#[derive(Default)]
+struct MacroCatalog {
+    macros: Vec<MacroInfo>,
+    /// `(macro name, member)` from tables whose first column links to a macro page
+    overview: Vec<(String, GeneratedMember)>,
+    /// Entries of the lists under `... Validators` headings
+    validators: Vec<Validator>,
+    /// What each macro's `Generated ...` section says about each target
+    target_notes: Vec<TargetNote>,
+    /// `<Target> Support` sections, e.g. `Enum Support` on the overview page
+    support: Vec<SupportSection>,
+}
+
+/// Reads a builtin-macros page. Pages with a `Generated ...` heading become macro
+/// entries: its table rows or `**Target**:` paragraphs give targets and members, and
+/// the lists under `... Options` headings give decorator options. Lists under
+/// `<Type> Validators` headings are collected for the `@serde` reference, and
+/// `<Target> Support` sections for the support matrix.
+fn catalog_macro_page(
+    catalog: &mut MacroCatalog,
+    page_id: &str,
+    title: &str,
+    summary: &str,
+    source: &PageSource,
+    blocks: &[Block],
+    chunks: &[Chunk],
+) {
+    let markdown = &source.markdown;
+    let leaves = page_leaves(page_id, markdown.len(), chunks);
+    let code_re = Regex::new(r"`([^`]+)`").unwrap();
+    let link_re = Regex::new(r"^\[`?([^`\]]+)`?\]\(").unwrap();
+    let label_re = Regex::new(r"^\*\*([^*]+)\*\*:?").unwrap();
+
+    let mut has_generated = false;
+    let mut generated_level: Option<usize> = None;
+    let mut options_level: Option<(usize, OptionLevel)> = None;
+    let mut validators_level: Option<(usize, String)> = None;
+    let mut support_level: Option<usize> = None;
+    let mut targets = Vec::new();
+    let mut generated: Vec<GeneratedMember> = Vec::new();
+    let mut options = Vec::new();
+    let mut decorator: Option<String> = None;
+
+    for block in blocks {
+        let text = &markdown[block.start..block.end];
+        let section_id = leaf_at(&leaves, page_id, block.start);
+        match &block.kind {
+            BlockKind::Heading { level, text } => {
+                if generated_level.is_some_and(|l| *level <= l) {
+                    generated_level = None;
+                }
+                if options_level.is_some_and(|(l, _)| *level <= l) {
+                    options_level = None;
+                }
+                if validators_level.as_ref().is_some_and(|(l, _)| level <= l) {
+                    validators_level = None;
+                }
+                if support_level.is_some_and(|l| *level <= l) {
+                    support_level = None;
+                }
+                if text.starts_with("Generated") {
+                    has_generated = true;
+                    generated_level = Some(*level);
+                } else if text.ends_with("Options") {
+                    let kind = if text.starts_with("Container") {
+                        OptionLevel::Container
+                    } else {
+                        OptionLevel::Field
+                    };
+                    options_level = Some((*level, kind));
+                } else if let Some(category) = text.strip_suffix(" Validators") {
+                    validators_level = Some((*level, category.to_string()));
+                } else if let Some(target) = text.strip_suffix(" Support").and_then(macro_target) {
+                    support_level = Some(*level);
+                    catalog.support.push(SupportSection {
+                        target,
+                        heading: text.clone(),
+                        section_id: leaf_at(&leaves, page_id, block.start),
+                        note: String::new(),
+                    });
+                }
+            }
+            BlockKind::Table if generated_level.is_none() => {
+                for row in table_rows(text) {
+                    let Some(name) = link_re.captures(&row[0]) else {
+                        continue;
+                    };
+                    let spans = row.get(1).map_or("", String::as_str);
+                    let targets = [MacroTarget::Class];
+                    for member in generated_members(&code_re, spans, &targets, &section_id) {
+                        catalog.overview.push((name[1].to_string(), member));
+                    }
+                }
+            }
+            BlockKind::Table | BlockKind::Paragraph | BlockKind::List
+                if generated_level.is_some() =>
+            {
+                // (target label, text with the generated code, description)
+                let rows: Vec<(String, String, String)> = if block.kind == BlockKind::Table {
+                    table_rows(text)
+                        .into_iter()
+                        .map(|row| {
+                            let note = row.get(2..).and_then(<[_]>::last).cloned();
+                            (row[0].clone(), row[1..].join(" | "), note.unwrap_or_default())
+                        })
+                        .collect()
+                } else {
+                    list_entries(text)
+                        .into_iter()
+                        .filter_map(|entry| {
+                            let label = label_re.captures(&entry)?;
+                            let rest = entry[label[0].len()..].trim().to_string();
+                            Some((label[1].to_string(), rest.clone(), rest))
+                        })
+                        .collect()
+                };
+                for (label, rest, note) in rows {
+                    let Some(target) = macro_target(&label) else {
+                        continue;
+                    };
+                    targets.push(target);
+                    if !note.is_empty() {
+                        catalog.target_notes.push(TargetNote {
+                            macro_name: title.to_string(),
+                            target,
+                            note,
+                            section_id: section_id.clone(),
+                        });
+                    }
+                    for member in generated_members(&code_re, &rest, &[target], &section_id) {
+                        merge_member(&mut generated, member);
+                    }
+                }
+            }
+            BlockKind::Paragraph if support_level.is_some() => {
+                if let Some(support) = catalog.support.last_mut().filter(|s| s.note.is_empty()) {
+                    support.note = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                }
+            }
+            BlockKind::Paragraph if options_level.is_some() && decorator.is_none() => {
+                decorator = code_re
+                    .captures_iter(text)
+                    .map(|c| c[1].to_string())
+                    .find(|span| is_decorator(span));
+            }
+            BlockKind::List => {
+                if let Some((_, category)) = &validators_level {
+                    for entry in list_entries(text) {
+                        let found = validator_entries(&code_re, &entry, category, &section_id);
+                        catalog.validators.extend(found);
+                    }
+                } else if let Some((_, level)) = options_level {
+                    for entry in list_entries(text) {
+                        if let Some(option) = macro_option(&code_re, &entry, level, &section_id) {
+                            merge_option(&mut options, option);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !has_generated {
+        return;
+    }
+    targets.sort();
+    targets.dedup();
+    // `@default(42)`-style options name the decorator themselves
+    let decorator = decorator.or_else(|| {
+        options.iter().flat_map(|o: &MacroOption| &o.syntax).find_map(|syntax| {
+            let name = syntax.split('(').next().unwrap_or("");
+            is_decorator(name).then(|| name.to_string())
+        })
+    });
+
+    catalog.macros.push(MacroInfo {
+        name: title.to_string(),
+        section_id: page_id.to_string(),
+        description: summary.to_string(),
+        decorator,
+        targets,
+        generated,
+        options,
+        example_pairs: Vec::new(),
+    });
+}
+
+/// Adds the overview's members to their macros and links each macro to the example
+/// pairs on its page. A macro's own page is authoritative, so the overview's one-line
+/// form (e.g. `clone(): T`) only fills in targets the page documents no members for.
+fn finish_macro_catalog(catalog: MacroCatalog, pairs: &[ExamplePair]) -> Vec<MacroInfo> {
+    let mut macros = catalog.macros;
+    for (name, member) in catalog.overview {
+        if let Some(info) = macros.iter_mut().find(|m| m.name.eq_ignore_ascii_case(&name)) {
+            let documented = info
+                .generated
+                .iter()
+                .any(|m| m.targets.iter().any(|t| member.targets.contains(t)));
+            if !documented {
+                merge_member(&mut info.generated, member);
+            }
+        }
+    }
+    for info in &mut macros {
+        let prefix = format!("{}/", info.section_id);
+        info.example_pairs = pairs
+            .iter()
+            .filter(|p| p.section_id == info.section_id || p.section_id.starts_with(&prefix))
+            .map(|p| p.id.clone())
+            .collect();
+    }
+    macros
+}
+
+/// Splits a table into trimmed cells, dropping the header and delimiter rows.
+fn table_rows(table: &str) -> Vec<Vec<String>> {
+    table
+        .lines()
+        .skip(2)
+        .map(|line| {
+            let line = line.trim().trim_start_matches('|').trim_end_matches('|');
+            line.split('|').map(|cell| cell.trim().to_string()).collect::<Vec<_>>()
+        })
+        .filter(|row| !row[0].is_empty())
+        .collect()
+}
+
+/// Splits a paragraph or list into entries, one per list item, joining wrapped lines.
+fn list_entries(text: &str) -> Vec<String> {
+    let mut entries: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        let ordered = line
+            .split_once(". ")
+            .filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .map(|(_, item)| item);
+        let bullet = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "));
+        if let Some(item) = bullet.or(ordered) {
+            entries.push(item.trim().to_string());
+        } else if let Some(last) = entries.last_mut().filter(|_| !line.is_empty()) {
+            last.push(' ');
+            last.push_str(line);
+        } else if !line.is_empty() {
+            entries.push(line.to_string());
+        }
+    }
+    entries
+}
+
+fn macro_target(label: &str) -> Option<MacroTarget> {
+    let label = label.to_lowercase();
+    if label.starts_with("class") {
+        Some(MacroTarget::Class)
+    } else if label.starts_with("interface") {
+        Some(MacroTarget::Interface)
+    } else if label.starts_with("enum") {
+        Some(MacroTarget::Enum)
+    } else if label.starts_with("type alias") {
+        Some(MacroTarget::TypeAlias)
+    } else {
+        None
+    }
+}
+
+/// Reads members from the code spans of a row: `static name(...)` is a static
+/// method, names built on a placeholder like `classNameClone(...)` are standalone
+/// functions, and anything else callable is an instance method.
+fn generated_members(
+    code_re: &Regex,
+    text: &str,
+    targets: &[MacroTarget],
+    section_id: &str,
+) -> Vec<GeneratedMember> {
+    let call_re = Regex::new(r"^(static\s+)?([A-Za-z_$][\w$]*)\s*\(").unwrap();
+    code_re
+        .captures_iter(text)
+        .filter_map(|c| {
+            // Table cells may separate words with non-breaking spaces
+            let signature = c[1].replace("&#124;", "|");
+            let signature = signature.split_whitespace().collect::<Vec<_>>().join(" ");
+            let call = call_re.captures(&signature)?;
+            let name = call[2].to_string();
+            let lower = name.to_lowercase();
+            let kind = if call.get(1).is_some() {
+                MemberKind::StaticMethod
+            } else if ["classname", "enumname", "interfacename", "typename"]
+                .iter()
+                .any(|p| lower.contains(p))
+            {
+                MemberKind::Function
+            } else {
+                MemberKind::Method
+            };
+            Some(GeneratedMember {
+                name,
+                kind,
+                signature: signature.clone(),
+                targets: targets.to_vec(),
+                section_id: section_id.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Adds a member, folding targets into an existing member of the same name and kind.
+fn merge_member(members: &mut Vec<GeneratedMember>, member: GeneratedMember) {
+    match members.iter_mut().find(|m| m.name == member.name && m.kind == member.kind) {
+        Some(existing) => {
+            existing.targets.extend(member.targets);
+            existing.targets.sort();
+            existing.targets.dedup();
+        }
+        None => members.push(member),
+    }
+}
+
+/// Adds an option, folding the syntax forms and descriptions of an option documented
+/// once per form (e.g. `@default(42)`, `@default("hello")`) into a single entry.
+fn merge_option(options: &mut Vec<MacroOption>, option: MacroOption) {
+    match options.iter_mut().find(|o| o.name == option.name) {
+        Some(existing) => {
+            for form in option.syntax {
+                if !existing.syntax.contains(&form) {
+                    existing.syntax.push(form);
+                }
+            }
+            for alias in option.aliases {
+                if !existing.aliases.contains(&alias) {
+                    existing.aliases.push(alias);
+                }
+            }
+            if !existing.description.contains(&option.description) {
+                existing.description = format!("{}; {}", existing.description, option.description);
+            }
+        }
+        None => options.push(option),
+    }
+}
+
+fn is_decorator(span: &str) -> bool {
+    span.strip_prefix('@').is_some_and(|name| {
+        name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Parses a ``- `name` / `alias = value` - Description`` list item.
+fn macro_option(
+    code_re: &Regex,
+    entry: &str,
+    level: OptionLevel,
+    section_id: &str,
+) -> Option<MacroOption> {
+    let (spans, description) = entry.split_once(" - ")?;
+    let syntax: Vec<String> = code_re
+        .captures_iter(spans)
+        .map(|c| c[1].split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    let separators = code_re.replace_all(spans, "");
+    if syntax.is_empty() || !separators.chars().all(|c| matches!(c, ' ' | '/' | ',')) {
+        return None;
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for form in &syntax {
+        let name: String = form
+            .trim_start_matches('@')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        return None;
+    }
+    let name = names.remove(0);
+
+    Some(MacroOption {
+        name,
+        aliases: names,
+        syntax,
+        level,
+        description: description.trim().to_string(),
+        section_id: section_id.to_string(),
+    })
+}
+
+// ============================================================================
+// Support Matrix
+// ============================================================================
+
+/// Id of the generated support matrix section
+const SUPPORT_SECTION_ID: &str = "macro-support";
+
+const ALL_TARGETS: [MacroTarget; 4] =
+    [MacroTarget::Class, MacroTarget::Interface, MacroTarget::Enum, MacroTarget::TypeAlias];
+
+/// A `Generated ...` row describing what a macro emits for one target.
+#[derive(Debug)]
+struct TargetNote {
+    macro_name: String,
+    target: MacroTarget,
+    note: String,
+    section_id: String,
+}
+
+/// A `<Target> Support` section and its opening paragraph.
+#[derive(Debug)]
+struct SupportSection {
+    target: MacroTarget,
+    heading: String,
+    section_id: String,
+    note: String,
+}
+
+/// Which targets a macro can be derived on, written to `macro-support.json`.
+#[derive(Debug, Serialize)]
+struct MacroSupport {
+    #[serde(rename = "macro")]
+    macro_name: String,
+    section_id: String,
+    targets: Vec<TargetSupport>,
+}
+
+#[derive(Debug, Serialize)]
+struct TargetSupport {
+    target: MacroTarget,
+    supported: bool,
+    notes: Vec<SupportNote>,
+    /// Ids in `examples.json` deriving the macro on this target
+    examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SupportNote {
+    text: String,
+    section_id: String,
+}
+
+/// Crosses every catalogued macro with every target. A target is supported when the
+/// macro's page has a row for it or a `<Target> Support` section says all built-in
+/// macros work with it; examples are the support section's samples deriving the macro.
+fn build_support_matrix(catalog: &MacroCatalog, examples: &[CodeExample]) -> Vec<MacroSupport> {
+    let derive_re = Regex::new(r"@derive\(([^)]*)\)").unwrap();
+
+    catalog
+        .macros
+        .iter()
+        .map(|info| {
+            let targets = ALL_TARGETS
+                .iter()
+                .map(|&target| {
+                    let mut notes: Vec<SupportNote> = catalog
+                        .target_notes
+                        .iter()
+                        .filter(|n| n.macro_name == info.name && n.target == target)
+                        .map(|n| SupportNote {
+                            text: plain_text(&n.note),
+                            section_id: n.section_id.clone(),
+                        })
+                        .collect();
+                    let mut supported = info.targets.contains(&target);
+                    let mut example_ids = Vec::new();
+
+                    for support in catalog.support.iter().filter(|s| s.target == target) {
+                        let all_macros = support.note.starts_with("All built-in macros");
+                        let derived: Vec<&CodeExample> = examples
+                            .iter()
+                            .filter(|e| {
+                                e.section_id == support.section_id
+                                    && e.heading.as_deref() == Some(support.heading.as_str())
+                            })
+                            .filter(|e| {
+                                derive_re.captures_iter(&e.code).any(|c| {
+                                    c[1].split(',').any(|m| m.trim() == info.name)
+                                })
+                            })
+                            .collect();
+                        if !all_macros && derived.is_empty() {
+                            continue;
+                        }
+                        supported = true;
+                        notes.push(SupportNote {
+                            text: support_note(&support.note),
+                            section_id: support.section_id.clone(),
+                        });
+                        example_ids.extend(derived.iter().map(|e| e.id.clone()));
+                    }
+
+                    TargetSupport { target, supported, notes, examples: example_ids }
+                })
+                .collect();
+
+            MacroSupport {
+                macro_name: info.name.clone(),
+                section_id: info.section_id.clone(),
+                targets,
+            }
+        })
+        .collect()
+}
+
+/// Plain text of a support section's opening paragraph, ending in a full stop rather
+/// than the colon introducing its example.
+fn support_note(paragraph: &str) -> String {
+    let text = plain_text(paragraph);
+    match text.strip_suffix(':') {
+        Some(lead) => format!("{}.", lead),
+        None => text,
+    }
+}
+
+/// Renders the support matrix as a markdown page: a macro × target table, the notes
+/// shared by every macro on a target, then each macro's own notes.
+fn render_support_matrix(matrix: &[MacroSupport], support: &[SupportSection]) -> String {
+    let label = |target: MacroTarget| match target {
+        MacroTarget::Class => "Class",
+        MacroTarget::Interface => "Interface",
+        MacroTarget::Enum => "Enum",
+        MacroTarget::TypeAlias => "Type Alias",
+    };
+
+    let mut md = String::from("# Macro Target Support\n\n");
+    md.push_str(
+        "Which TypeScript declarations each built-in macro can be derived on, \
+         collected from the built-in macro pages.\n\n",
+    );
+    md.push_str("| Macro |");
+    for target in ALL_TARGETS {
+        md.push_str(&format!(" {} |", label(target)));
+    }
+    md.push_str("\n|-------|");
+    md.push_str(&"---|".repeat(ALL_TARGETS.len()));
+    md.push('\n');
+    for entry in matrix {
+        md.push_str(&format!("| `{}` |", entry.macro_name));
+        for support in &entry.targets {
+            md.push_str(if support.supported { " Yes |" } else { " No |" });
+        }
+        md.push('\n');
+    }
+
+    if !support.is_empty() {
+        md.push_str("\n## Targets\n\n");
+        for section in support {
+            md.push_str(&format!(
+                "- **{}**: {} (`{}`)\n",
+                label(section.target),
+                support_note(&section.note),
+                section.section_id
+            ));
+        }
+    }
+
+    for entry in matrix {
+        md.push_str(&format!("\n## {}\n\n", entry.macro_name));
+        for cell in &entry.targets {
+            let notes: Vec<String> = cell
+                .notes
+                .iter()
+                .filter(|n| !support.iter().any(|s| s.section_id == n.section_id))
+                .map(|n| format!("{} (`{}`)", n.text.trim_end_matches('.'), n.section_id))
+                .collect();
+            let status = if cell.supported { "supported" } else { "not supported" };
+            md.push_str(&format!("- **{}**: {}", label(cell.target), status));
+            if !notes.is_empty() {
+                md.push_str(&format!(". {}", notes.join("; ")));
+            }
+            if !cell.examples.is_empty() {
+                md.push_str(&format!(". Examples: {}", cell.examples.join(", ")));
+            }
+            md.push('\n');
+        }
+    }
+
+    md
+}
+
+// ============================================================================
+// CLI Reference
+// ============================================================================
+
+/// A command documented under a `### macroforge <command>` heading, written to
+/// `cli.json`.
+#[derive(Debug, Serialize)]
+struct CliCommand {
+    /// Full command, e.g. `macroforge expand`
+    name: String,
+    /// First line of the usage block, e.g. `macroforge expand <input> [options]`
+    usage: String,
+    description: String,
+    section_id: String,
+    arguments: Vec<CliArgument>,
+    flags: Vec<CliFlag>,
+    examples: Vec<CliExample>,
+}
+
+#[derive(Debug, Serialize)]
+struct CliArgument {
+    name: String,
+    /// `<input>` is required, `[input]` optional
+    required: bool,
+    description: String,
+    section_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CliFlag {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    short: Option<String>,
+    /// Placeholder of the flag's value, e.g. `path`; absent for switches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    /// From a `(defaults to ...)` note in the description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    description: String,
+    section_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CliExample {
+    command: String,
+    /// Sentence introducing the example
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    section_id: String,
+    /// Id of the code block in `examples.json`
+    example_id: String,
+}
+
+/// Which part of a command's section a block belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CliPart {
+    /// Directly under the command heading
+    Summary,
+    Arguments,
+    Flags,
+    Other,
+}
+
+/// Finds the commands documented on a page: headings such as `macroforge expand`
+/// whose first code block starts with the heading text. Argument and option tables
+/// under the heading give arguments and flags; every line of a code block on the
+/// page starting with the command is an example. `examples` are the page's entries
+/// in `examples.json`, in document order.
+fn page_cli_commands(
+    page_id: &str,
+    source: &PageSource,
+    blocks: &[Block],
+    chunks: &[Chunk],
+    examples: &[CodeExample],
+) -> Vec<CliCommand> {
+    let markdown = &source.markdown;
+    let leaves = page_leaves(page_id, markdown.len(), chunks);
+    let command_re = Regex::new(r"^[a-z][a-z0-9_-]*( [a-z][a-z0-9_-]*)+$").unwrap();
+    let code_re = Regex::new(r"`([^`]+)`").unwrap();
+
+    // Commands with the byte range of their section
+    let mut commands: Vec<(CliCommand, usize, usize)> = Vec::new();
+    let mut open: Option<(usize, CliPart)> = None;
+    for block in blocks {
+        let section_id = leaf_at(&leaves, page_id, block.start);
+        if let BlockKind::Heading { level, text } = &block.kind {
+            if let Some((command_level, _)) = open {
+                if *level > command_level {
+                    let lower = text.to_lowercase();
+                    let part = if lower.contains("argument") {
+                        CliPart::Arguments
+                    } else if lower.contains("option") || lower.contains("flag") {
+                        CliPart::Flags
+                    } else {
+                        CliPart::Other
+                    };
+                    open = Some((command_level, part));
+                    continue;
+                }
+                open = None;
+                if let Some(last) = commands.last_mut() {
+                    last.2 = block.start;
+                }
+            }
+            let name = text.replace('`', "");
+            if command_re.is_match(&name) {
+                open = Some((*level, CliPart::Summary));
+                let command = CliCommand {
+                    name,
+                    usage: String::new(),
+                    description: String::new(),
+                    section_id,
+                    arguments: Vec::new(),
+                    flags: Vec::new(),
+                    examples: Vec::new(),
+                };
+                commands.push((command, block.start, markdown.len()));
+            }
+            continue;
+        }
+
+        let (Some((_, part)), Some((command, _, _))) = (open, commands.last_mut()) else {
+            continue;
+        };
+        let text = &markdown[block.start..block.end];
+        match (&block.kind, part) {
+            (BlockKind::Paragraph, CliPart::Summary) => {
+                let text = plain_text(text);
+                if command.description.is_empty() && !is_code_label(&text) {
+                    command.description = text;
+                }
+            }
+            (BlockKind::Table, CliPart::Arguments | CliPart::Flags) => {
+                for row in table_rows(text) {
+                    let spans: Vec<String> = code_re
+                        .captures_iter(&row[0])
+                        .map(|c| c[1].replace('\u{a0}', " "))
+                        .collect();
+                    let description = plain_text(row.last().map_or("", String::as_str));
+                    if part == CliPart::Flags {
+                        if !spans.is_empty() {
+                            let flag = cli_flag(&spans.join(" "), description, &section_id);
+                            command.flags.push(flag);
+                        }
+                        continue;
+                    }
+                    for span in spans {
+                        command.arguments.push(CliArgument {
+                            name: span.trim_matches(['<', '>', '[', ']']).to_string(),
+                            required: !span.starts_with('['),
+                            description: description.clone(),
+                            section_id: section_id.clone(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The first code block of a command's section is its usage; any line starting
+    // with a command is an example of it. Rendered pages may use no-break spaces.
+    let mut usage_checked = vec![false; commands.len()];
+    for (fence, example) in fenced_blocks(markdown).iter().zip(examples) {
+        let code = fence.code.replace('\u{a0}', " ");
+        let owner = commands
+            .iter()
+            .position(|(_, start, end)| fence.start >= *start && fence.start < *end);
+        if let Some(i) = owner.filter(|&i| !usage_checked[i]) {
+            usage_checked[i] = true;
+            let command = &mut commands[i].0;
+            let first = code.lines().next().unwrap_or("").trim();
+            if first.starts_with(&command.name) {
+                command.usage = first.to_string();
+            }
+            continue;
+        }
+
+        let description = fence.context.as_ref().map(|c| c.trim_end_matches(':').to_string());
+        for line in code.lines().map(str::trim) {
+            let matched = commands
+                .iter_mut()
+                .map(|(command, _, _)| command)
+                .filter(|c| line == c.name || line.starts_with(&format!("{} ", c.name)))
+                .max_by_key(|c| c.name.len());
+            if let Some(command) = matched {
+                command.examples.push(CliExample {
+                    command: line.to_string(),
+                    description: description.clone(),
+                    section_id: example.section_id.clone(),
+                    example_id: example.id.clone(),
+                });
+            }
+        }
+    }
+
+    commands.into_iter().map(|(command, _, _)| command).filter(|c| !c.usage.is_empty()).collect()
+}
+
+/// Parses a flag cell such as `-p, --project <path>`.
+fn cli_flag(spec: &str, description: String, section_id: &str) -> CliFlag {
+    let default_re = Regex::new(r"(?i)\(defaults? (?:to|:)\s*([^)]*)\)").unwrap();
+    let mut flag = CliFlag {
+        long: None,
+        short: None,
+        value: None,
+        default: default_re.captures(&description).map(|c| {
+            let default = c[1].trim();
+            let code = default.split('`').nth(1).filter(|_| default.contains('`'));
+            code.unwrap_or(default).to_string()
+        }),
+        description,
+        section_id: section_id.to_string(),
+    };
+    for token in spec.split([',', ' ']).filter(|t| !t.is_empty()) {
+        if token.starts_with("--") {
+            flag.long = Some(token.to_string());
+        } else if token.starts_with('-') {
+            flag.short = Some(token.to_string());
+        } else {
+            flag.value = Some(token.trim_matches(['<', '>', '[', ']']).to_string());
+        }
+    }
+    flag
+}
+
+// ============================================================================
+// Config Schema
+// ============================================================================
+
+/// Configuration file whose options are turned into a JSON Schema
+const CONFIG_FILE_NAME: &str = "macroforge.json";
+
+/// An option documented under its own heading on the configuration page, e.g.
+/// `### allowNativeMacros` followed by a `| Type | ... | Default | ... |` line.
+#[derive(Debug)]
+struct ConfigOption {
+    /// Key path; dotted headings such as `limits.maxDiagnostics` are nested
+    path: Vec<String>,
+    ty: Option<String>,
+    default: Option<String>,
+    description: String,
+    section_id: String,
+}
+
+/// Options documented in the configuration docs but never used in an example, or
+/// used in an example but never documented. Written to `config-report.json`.
+#[derive(Debug, Default, Serialize)]
+struct ConfigReport {
+    undocumented: Vec<ConfigReportEntry>,
+    unused: Vec<ConfigReportEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigReportEntry {
+    /// Dotted key path, e.g. `limits.maxMemoryBytes`
+    key: String,
+    /// Example ids for undocumented keys, the documenting section for unused ones
+    sources: Vec<String>,
+}
+
+/// The configuration page is the one with an example labelled `macroforge.json`.
+fn is_config_page(examples: &[CodeExample]) -> bool {
+    examples.iter().any(|e| e.file_name.as_deref() == Some(CONFIG_FILE_NAME))
+}
+
+/// Reads the options documented on a page: headings that are a bare (optionally
+/// dotted) camelCase key, their type and default line and first paragraph.
+fn page_config_options(
+    page_id: &str,
+    source: &PageSource,
+    blocks: &[Block],
+    chunks: &[Chunk],
+) -> Vec<ConfigOption> {
+    let markdown = &source.markdown;
+    let leaves = page_leaves(page_id, markdown.len(), chunks);
+    let key_re = Regex::new(r"^[a-z][A-Za-z0-9]*(\.[a-z][A-Za-z0-9]*)*$").unwrap();
+    let type_re = Regex::new(r"(?m)^\W*Type\W*`([^`]+)`").unwrap();
+    let default_re = Regex::new(r"Default\W*`([^`]+)`").unwrap();
+
+    let mut options: Vec<ConfigOption> = Vec::new();
+    let mut open: Option<usize> = None;
+    for block in blocks {
+        let text = &markdown[block.start..block.end];
+        match &block.kind {
+            BlockKind::Heading { level, text } => {
+                if open.is_some_and(|l| *level > l) {
+                    continue;
+                }
+                let key = text.replace('`', "");
+                open = key_re.is_match(&key).then_some(*level);
+                if open.is_some() {
+                    options.push(ConfigOption {
+                        path: key.split('.').map(String::from).collect(),
+                        ty: None,
+                        default: None,
+                        description: String::new(),
+                        section_id: leaf_at(&leaves, page_id, block.start),
+                    });
+                }
+            }
+            BlockKind::Paragraph | BlockKind::Table if open.is_some() => {
+                let Some(option) = options.last_mut() else {
+                    continue;
+                };
+                if let Some(ty) = type_re.captures(text) {
+                    option.ty = Some(ty[1].to_string());
+                    option.default = default_re.captures(text).map(|d| d[1].to_string());
+                } else if option.description.is_empty() {
+                    let text = plain_text(text);
+                    if !is_code_label(&text) {
+                        option.description = text;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    options
+}
+
+/// Builds a JSON Schema for the configuration file from the documented options and
+/// the JSON examples on the configuration page, and reports keys that only one of
+/// the two mentions. Keys only seen in examples are still added, typed from their
+/// example values, so the schema accepts every configuration the docs show.
+fn build_config_schema(
+    summary: &str,
+    options: &[ConfigOption],
+    examples: &[&CodeExample],
+) -> (serde_json::Value, ConfigReport) {
+    let mut schema = serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": CONFIG_FILE_NAME,
+        "description": summary,
+        "type": "object",
+        "properties": {},
+        "additionalProperties": false,
+    });
+
+    for option in options {
+        let property = schema_property(&mut schema, &option.path);
+        if let Some(ty) = &option.ty {
+            merge_object(property, ts_type_schema(ty));
+        }
+        if let Some(default) = &option.default {
+            let value = serde_json::from_str(default)
+                .unwrap_or_else(|_| serde_json::Value::String(default.clone()));
+            property["default"] = value;
+        }
+        if !option.description.is_empty() {
+            property["description"] = option.description.clone().into();
+        }
+        property["x-section-id"] = option.section_id.clone().into();
+    }
+
+    // Dotted key path -> ids of the examples using it
+    let mut used: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for example in examples {
+        let Some((value, comments)) = parse_jsonc(&example.code) else {
+            continue;
+        };
+        let mut keys = Vec::new();
+        example_keys(&value, &mut Vec::new(), &mut keys);
+        for (path, value) in keys {
+            let key = path.join(".");
+            let ids = used.entry(key).or_default();
+            if !ids.contains(&example.id) {
+                ids.push(example.id.clone());
+            }
+
+            let property = schema_property(&mut schema, &path);
+            if property.get("type").is_none() {
+                merge_object(property, infer_schema(value));
+            }
+            if property.get("description").is_none() {
+                if let Some(comment) = path.last().and_then(|k| comments.get(k)) {
+                    property["description"] = comment.clone().into();
+                }
+            }
+            if !property.get("x-section-id").is_some_and(|s| s.is_string()) {
+                property["x-section-id"] = example.section_id.clone().into();
+            }
+            if property["examples"].as_array().is_none_or(|e| e.len() < 3)
+                && !value.is_object()
+            {
+                let examples = property["examples"].as_array().cloned().unwrap_or_default();
+                if !examples.contains(value) {
+                    let mut examples = examples;
+                    examples.push(value.clone());
+                    property["examples"] = examples.into();
+                }
+            }
+        }
+    }
+
+    let documented: HashSet<String> = options.iter().map(|o| o.path.join(".")).collect();
+    let mut report = ConfigReport::default();
+    for (key, ids) in &used {
+        // A key is covered by its own option, an enclosing one, or options nested in it
+        let related = documented.iter().any(|d| {
+            key.starts_with(&format!("{}.", d)) || d.starts_with(&format!("{}.", key))
+        });
+        if !documented.contains(key) && !related {
+            report.undocumented.push(ConfigReportEntry { key: key.clone(), sources: ids.clone() });
+        }
+    }
+    for option in options {
+        let key = option.path.join(".");
+        if !used.contains_key(&key) {
+            let sources = vec![option.section_id.clone()];
+            report.unused.push(ConfigReportEntry { key, sources });
+        }
+    }
+
+    (schema, report)
+}
+
+/// Returns the schema for a key path, creating `properties` objects along the way.
+fn schema_property<'a>(
+    schema: &'a mut serde_json::Value,
+    path: &[String],
+) -> &'a mut serde_json::Value {
+    let mut node = schema;
+    for key in path {
+        if node.get("type").is_none() {
+            node["type"] = "object".into();
+        }
+        if !node.get("properties").is_some_and(|p| p.is_object()) {
+            node["properties"] = serde_json::json!({});
+            node["additionalProperties"] = false.into();
+        }
+        let properties = &mut node["properties"];
+        if properties.get(key).is_none() {
+            properties[key] = serde_json::json!({});
+        }
+        node = &mut properties[key];
+    }
+    node
+}
+
+fn merge_object(target: &mut serde_json::Value, source: serde_json::Value) {
+    if let (Some(target), serde_json::Value::Object(source)) = (target.as_object_mut(), source) {
+        for (key, value) in source {
+            target.entry(key).or_insert(value);
+        }
+    }
+}
+
+/// Translates a documented TypeScript type (`boolean`, `string[]`, `"a" | "b"`,
+/// `Record<string, number>`) into a JSON Schema.
+fn ts_type_schema(ty: &str) -> serde_json::Value {
+    let ty = ty.trim();
+    let variants: Vec<&str> = ty.split('|').map(str::trim).collect();
+    if variants.len() > 1 {
+        if variants.iter().all(|v| v.starts_with('"')) {
+            let values: Vec<&str> = variants.iter().map(|v| v.trim_matches('"')).collect();
+            return serde_json::json!({ "type": "string", "enum": values });
+        }
+        let any_of: Vec<serde_json::Value> = variants.into_iter().map(ts_type_schema).collect();
+        return serde_json::json!({ "anyOf": any_of });
+    }
+    if let Some(item) = ty.strip_suffix("[]") {
+        return serde_json::json!({ "type": "array", "items": ts_type_schema(item) });
+    }
+    if let Some(value) = ty.strip_prefix("Record<").and_then(|r| r.strip_suffix('>')) {
+        let value = value.split_once(',').map_or(value, |(_, v)| v);
+        let value = ts_type_schema(value);
+        return serde_json::json!({ "type": "object", "additionalProperties": value });
+    }
+    match ty {
+        "boolean" | "string" | "number" | "null" | "object" => serde_json::json!({ "type": ty }),
+        _ => serde_json::json!({}),
+    }
+}
+
+/// Infers a schema from an example value. Objects keyed by package names rather
+/// than option names (`"@my-org/macros": {...}`) are maps, whose values may set
+/// keys the example leaves out; other objects only allow the keys shown.
+fn infer_schema(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Bool(_) => serde_json::json!({ "type": "boolean" }),
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => {
+            serde_json::json!({ "type": "integer" })
+        }
+        serde_json::Value::Number(_) => serde_json::json!({ "type": "number" }),
+        serde_json::Value::String(_) => serde_json::json!({ "type": "string" }),
+        serde_json::Value::Array(items) => match items.first() {
+            Some(item) => serde_json::json!({ "type": "array", "items": infer_schema(item) }),
+            None => serde_json::json!({ "type": "array" }),
+        },
+        serde_json::Value::Object(map) if map.keys().any(|k| !is_config_key(k)) => {
+            let mut value = map.values().next().map_or(serde_json::json!({}), infer_schema);
+            if let Some(value) = value.as_object_mut() {
+                value.remove("additionalProperties");
+            }
+            serde_json::json!({ "type": "object", "additionalProperties": value })
+        }
+        serde_json::Value::Object(map) => {
+            let properties: serde_json::Map<String, serde_json::Value> =
+                map.iter().map(|(k, v)| (k.clone(), infer_schema(v))).collect();
+            serde_json::json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            })
+        }
+        serde_json::Value::Null => serde_json::json!({ "type": "null" }),
+    }
+}
+
+fn is_config_key(key: &str) -> bool {
+    key.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Collects the option keys an example sets, descending into objects whose keys are
+/// option names.
+fn example_keys<'a>(
+    value: &'a serde_json::Value,
+    path: &mut Vec<String>,
+    keys: &mut Vec<(Vec<String>, &'a serde_json::Value)>,
+) {
+    let Some(map) = value.as_object() else {
+        return;
+    };
+    if map.keys().any(|k| !is_config_key(k)) {
+        return;
+    }
+    for (key, value) in map {
+        path.push(key.clone());
+        keys.push((path.clone(), value));
+        example_keys(value, path, keys);
+        path.pop();
+    }
+}
+
+/// Parses JSON with `//` comments and trailing commas. Returns the value and, for
+/// each key, the comment lines directly above it.
+fn parse_jsonc(code: &str) -> Option<(serde_json::Value, HashMap<String, String>)> {
+    let key_re = Regex::new(r#"^\s*"([^"]+)"\s*:"#).unwrap();
+    let trailing_comma_re = Regex::new(r",(\s*[}\]])").unwrap();
+    let mut comments = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut json = String::new();
+
+    for line in code.replace('\u{a0}', " ").lines() {
+        let (content, comment) = split_line_comment(line);
+        if content.trim().is_empty() {
+            if let Some(comment) = comment {
+                pending.push(comment.trim().to_string());
+            }
+            json.push('\n');
+            continue;
+        }
+        if let Some(key) = key_re.captures(content) {
+            if !pending.is_empty() {
+                comments.insert(key[1].to_string(), pending.join(" "));
+            }
+        }
+        pending.clear();
+        json.push_str(content);
+        json.push('\n');
+    }
+
+    let json = trailing_comma_re.replace_all(&json, "$1");
+    serde_json::from_str(&json).ok().map(|value| (value, comments))
+}
+
+/// Splits a line at a `//` comment outside string literals.
+fn split_line_comment(line: &str) -> (&str, Option<&str>) {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '/' if !in_string && line[i..].starts_with("//") => {
+                return (&line[..i], Some(&line[i + 2..]));
+            }
+            _ => {}
+        }
+    }
+    (line, None)
+}
+
+// ============================================================================
+// Error Catalog
+// ============================================================================
+
+/// A documented error, written to `errors.json` so diagnostics can point at the
+/// docs explaining them.
+#[derive(Debug, Serialize)]
+struct ErrorEntry {
+    /// `<section id>#error-<n>`
+    id: String,
+    kind: ErrorKind,
+    /// Message as shown in the docs, or the documented cause for conditions
+    message: String,
+    /// Regular expression matching the message, with names and quoted values
+    /// replaced by wildcards
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    /// Decorators or code a diagnostic must mention, for entries without a pattern
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+    /// Built-in macro whose page documents the error
+    #[serde(rename = "macro", skip_serializing_if = "Option::is_none")]
+    macro_name: Option<String>,
+    explanation: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<String>,
+    section_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ErrorKind {
+    /// Reported while expanding macros, e.g. `MacroforgeError::new(span, "...")`
+    Diagnostic,
+    /// A documented cause of an expansion error ("The macro will return an error if:")
+    Condition,
+    /// Thrown or returned by generated code, e.g. `missing required field`
+    Runtime,
+    /// A problem and its fixes from a Troubleshooting section
+    Troubleshooting,
+}
+
+/// Collects the errors documented on a page: the conditions listed in "Error
+/// Handling" sections, the problems under "Troubleshooting" headings, and the error
+/// messages appearing in code blocks. `examples` are the page's entries in
+/// `examples.json`, in document order.
+fn page_errors(
+    page_id: &str,
+    source: &PageSource,
+    blocks: &[Block],
+    chunks: &[Chunk],
+    examples: &[CodeExample],
+) -> Vec<ErrorEntry> {
+    let markdown = &source.markdown;
+    let leaves = page_leaves(page_id, markdown.len(), chunks);
+    let error_heading_re = Regex::new(r"(?i)\b(?:errors?|diagnostics?)\b|troubleshoot").unwrap();
+    let code_re = Regex::new(r"`([^`]+)`").unwrap();
+
+    let mut errors: Vec<ErrorEntry> = Vec::new();
+    // Level of the open error section and whether it is a Troubleshooting section
+    let mut open: Option<(usize, bool)> = None;
+    // Byte ranges of the error sections, the only place messages are taken from code
+    let mut error_ranges: Vec<(usize, usize)> = Vec::new();
+    let mut troubleshooting: Option<ErrorEntry> = None;
+    let mut intro: Option<String> = None;
+    for block in blocks {
+        let section_id = leaf_at(&leaves, page_id, block.start);
+        let text = &markdown[block.start..block.end];
+        match &block.kind {
+            BlockKind::Heading { level, text } => {
+                errors.extend(troubleshooting.take());
+                intro = None;
+                match open {
+                    Some((open_level, true)) if *level > open_level => {
+                        troubleshooting = Some(ErrorEntry {
+                            id: String::new(),
+                            kind: ErrorKind::Troubleshooting,
+                            message: text.clone(),
+                            pattern: None,
+                            keywords: Vec::new(),
+                            macro_name: None,
+                            explanation: String::new(),
+                            fixes: Vec::new(),
+                            section_ids: vec![section_id],
+                        });
+                    }
+                    Some((open_level, _)) if *level > open_level => {}
+                    _ => {
+                        if let Some(range) = error_ranges.last_mut().filter(|_| open.is_some()) {
+                            range.1 = block.start;
+                        }
+                        open = error_heading_re
+                            .is_match(text)
+                            .then(|| (*level, text.to_lowercase().contains("troubleshoot")));
+                        if open.is_some() {
+                            error_ranges.push((block.start, markdown.len()));
+                        }
+                    }
+                }
+            }
+            BlockKind::Paragraph if open.is_some() => {
+                let text = plain_text(text);
+                if is_code_label(&text) {
+                    continue;
+                }
+                match troubleshooting.as_mut() {
+                    Some(entry) if text.ends_with(':') && entry.explanation.is_empty() => {
+                        entry.explanation = text.trim_end_matches(':').to_string();
+                    }
+                    Some(entry) => entry.fixes.push(text),
+                    None => {
+                        intro = Some(text).filter(|t| t.ends_with("if:") || t.ends_with("when:"));
+                    }
+                }
+            }
+            BlockKind::List if open.is_some() => {
+                if let Some(entry) = troubleshooting.as_mut() {
+                    entry.fixes.extend(list_entries(text).iter().map(|item| plain_text(item)));
+                    continue;
+                }
+                let Some(intro) = intro.take() else {
+                    continue;
+                };
+                for item in list_entries(text) {
+                    let cause = plain_text(&item);
+                    let mut lowered = cause.clone();
+                    if let Some(first) = lowered.get_mut(..1) {
+                        first.make_ascii_lowercase();
+                    }
+                    errors.push(ErrorEntry {
+                        id: String::new(),
+                        kind: ErrorKind::Condition,
+                        message: cause,
+                        pattern: None,
+                        keywords: code_re.captures_iter(&item).map(|c| c[1].to_string()).collect(),
+                        macro_name: None,
+                        explanation: format!("{} {}", intro.trim_end_matches(':'), lowered),
+                        fixes: Vec::new(),
+                        section_ids: vec![section_id.clone()],
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    errors.extend(troubleshooting);
+    for entry in &mut errors {
+        if entry.explanation.is_empty() {
+            entry.explanation = entry.message.clone();
+        }
+    }
+
+    // Messages in code elsewhere are examples, e.g. what a custom macro might report
+    for (fence, example) in fenced_blocks(markdown).iter().zip(examples) {
+        if !error_ranges.iter().any(|&(start, end)| (start..end).contains(&fence.start)) {
+            continue;
+        }
+        let explanation = fence.context.as_deref().unwrap_or("").trim_end_matches(':');
+        for (kind, message) in code_error_messages(&fence.code.replace('\u{a0}', " ")) {
+            errors.push(ErrorEntry {
+                id: String::new(),
+                kind,
+                pattern: Some(message_pattern(&message)),
+                message,
+                keywords: Vec::new(),
+                macro_name: None,
+                explanation: explanation.to_string(),
+                fixes: Vec::new(),
+                section_ids: vec![example.section_id.clone()],
+            });
+        }
+    }
+    errors
+}
+
+/// Finds error messages in a code block: messages passed to `MacroforgeError::new`,
+/// diagnostics printed by the CLI, `message: '...'` properties of error objects and
+/// `// Logs: ...` comments.
+fn code_error_messages(code: &str) -> Vec<(ErrorKind, String)> {
+    let macro_error_re =
+        Regex::new(r#"MacroforgeError::new\(\s*[^,]+,\s*"((?:[^"\\]|\\.)*)""#).unwrap();
+    let cli_re = Regex::new(r"(?m)^\[macroforge\] (?:error|warning) at \S+: (.+)$").unwrap();
+    let property_re = Regex::new(r#"\bmessage:\s*(?:'([^']+)'|"([^"]+)")"#).unwrap();
+    let logs_re = Regex::new(r"(?m)//\s*(?:Logs|Throws|Error):\s*(.+)$").unwrap();
+    let qualified_re = Regex::new(r"\b[A-Z]\w*\.\w+: ").unwrap();
+
+    let mut messages: Vec<(ErrorKind, String)> = Vec::new();
+    let mut push = |kind, message: &str| {
+        let message = message.trim().to_string();
+        if !messages.iter().any(|(_, m)| *m == message) {
+            messages.push((kind, message));
+        }
+    };
+    for c in macro_error_re.captures_iter(code) {
+        push(ErrorKind::Diagnostic, &c[1]);
+    }
+    for c in cli_re.captures_iter(code) {
+        push(ErrorKind::Diagnostic, &c[1]);
+    }
+    for c in property_re.captures_iter(code) {
+        push(ErrorKind::Runtime, c.get(1).or(c.get(2)).map_or("", |m| m.as_str()));
+    }
+    for c in logs_re.captures_iter(code) {
+        // Drop the example's own prefix before the generated `Type.method: ` message
+        let message = &c[1];
+        let start = qualified_re.find(message).map_or(0, |m| m.start());
+        push(ErrorKind::Runtime, &message[start..]);
+    }
+    messages
+}
+
+/// Turns a documented message into an anchored regular expression (compatible with
+/// JavaScript's) matching the same message for other classes, macros and fields.
+fn message_pattern(message: &str) -> String {
+    let variable_re = Regex::new(
+        r#""[^"]*"|'[^']*'|@derive\(\w+\)|\b[A-Z]\w*\.|: [A-Z][a-z]+[A-Z]\w*$"#,
+    )
+    .unwrap();
+    let mut pattern = String::from("^");
+    let mut last = 0;
+    for m in variable_re.find_iter(message) {
+        pattern.push_str(&regex::escape(&message[last..m.start()]));
+        let text = m.as_str();
+        pattern.push_str(match text.chars().next() {
+            Some('"') => r#""[^"]*""#,
+            Some('\'') => r"'[^']*'",
+            Some('@') => r"@derive\(\w+\)",
+            Some(':') => r": \w+",
+            _ => r"\w+\.",
+        });
+        last = m.end();
+    }
+    pattern.push_str(&regex::escape(&message[last..]));
+    pattern.push('$');
+    pattern
+}
+
+/// Merges messages documented on several pages, numbers the entries, attributes them
+/// to the built-in macro whose page documents them, and suggests that macro's
+/// decorator options as fixes for conditions that mention them.
+fn finish_error_catalog(found: Vec<ErrorEntry>, macros: &[MacroInfo]) -> Vec<ErrorEntry> {
+    let mut errors: Vec<ErrorEntry> = Vec::new();
+    for entry in found {
+        let same = errors.iter_mut().find(|e| e.pattern.is_some() && e.pattern == entry.pattern);
+        match same {
+            Some(same) => {
+                for section_id in entry.section_ids {
+                    if !same.section_ids.contains(&section_id) {
+                        same.section_ids.push(section_id);
+                    }
+                }
+                if same.explanation.is_empty() {
+                    same.explanation = entry.explanation;
+                }
+            }
+            None => errors.push(entry),
+        }
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for entry in &mut errors {
+        let section_id = entry.section_ids[0].clone();
+        let n = counts.entry(section_id.clone()).or_default();
+        *n += 1;
+        entry.id = format!("{}#error-{}", section_id, n);
+
+        let Some(info) = macros.iter().find(|m| {
+            section_id == m.section_id || section_id.starts_with(&format!("{}/", m.section_id))
+        }) else {
+            continue;
+        };
+        entry.macro_name = Some(info.name.clone());
+        if entry.explanation.is_empty() && entry.kind == ErrorKind::Runtime {
+            entry.explanation = format!("Raised by the code `@derive({})` generates", info.name);
+        }
+        for keyword in entry.keywords.iter().filter(|k| is_decorator(k)) {
+            let option = info
+                .options
+                .iter()
+                .find(|o| o.syntax.first().is_some_and(|s| s.starts_with(keyword.as_str())));
+            let Some(option) = option else {
+                continue;
+            };
+            entry.fixes.push(format!(
+                "Add `{}`, e.g. `{}` ({})",
+                keyword,
+                option.syntax[0],
+                option.description.trim_end_matches('.')
+            ));
+            if !entry.section_ids.contains(&option.section_id) {
+                entry.section_ids.push(option.section_id.clone());
+            }
+        }
+    }
+
+    for entry in &mut errors {
+        if entry.explanation.is_empty() {
+            entry.explanation = match entry.kind {
+                ErrorKind::Diagnostic => "Reported by a macro during expansion".to_string(),
+                _ => entry.message.clone(),
+            };
+        }
+    }
+    errors
+}
+
+// ============================================================================
+// Template Syntax
+// ============================================================================
+
+/// Id of the generated `ts_template!` quick reference, sized to fit a single chunk
+const TEMPLATE_SYNTAX_SECTION_ID: &str = "ts-quote-syntax";
+
+/// The `ts_template!` grammar from the template syntax page, written to
+/// `ts-quote-syntax.json` and rendered as the `ts-quote-syntax` section.
+#[derive(Debug, Serialize)]
+struct TemplateSyntax {
+    /// Id of the template syntax page
+    section_id: String,
+    title: String,
+    summary: String,
+    macros: Vec<TemplateMacro>,
+    /// Rows of the page's quick reference table
+    forms: Vec<SyntaxForm>,
+    constructs: Vec<TemplateConstruct>,
+}
+
+/// A template macro from the "Available Macros" table, e.g. `body!`.
+#[derive(Debug, Serialize)]
+struct TemplateMacro {
+    name: String,
+    output: String,
+    use_case: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SyntaxForm {
+    /// e.g. `{#for item in list}...{/for}`
+    syntax: String,
+    description: String,
+    /// Id of the construct documenting the form
+    #[serde(skip_serializing_if = "Option::is_none")]
+    construct: Option<String>,
+}
+
+/// A construct documented under a heading such as ``Iteration: `{#for}` ``.
+#[derive(Debug, Serialize)]
+struct TemplateConstruct {
+    id: String,
+    name: String,
+    /// Syntax from the heading; several for headings naming more than one form
+    syntax: Vec<String>,
+    description: String,
+    section_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    example: Option<TemplateExample>,
+}
+
+/// The first Rust example of a construct and the TypeScript it generates.
+#[derive(Debug, Serialize)]
+struct TemplateExample {
+    example_id: String,
+    code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+/// Reads the template syntax page: a page with a "Quick Reference" table of
+/// `Syntax | Description` rows and headings of the form ``Name: `syntax` ``.
+/// `examples` are the page's entries in `examples.json`, in document order.
+fn page_template_syntax(
+    page_id: &str,
+    title: &str,
+    source: &PageSource,
+    blocks: &[Block],
+    chunks: &[Chunk],
+    examples: &[CodeExample],
+) -> Option<TemplateSyntax> {
+    let markdown = &source.markdown;
+    let leaves = page_leaves(page_id, markdown.len(), chunks);
+    let construct_re = Regex::new(r"^([^:`]+):\s*(`.+`)$").unwrap();
+    let code_re = Regex::new(r"`([^`]+)`").unwrap();
+
+    let mut syntax = TemplateSyntax {
+        section_id: page_id.to_string(),
+        title: title.to_string(),
+        summary: summarize(markdown),
+        macros: Vec::new(),
+        forms: Vec::new(),
+        constructs: Vec::new(),
+    };
+    // Text of the latest heading, level of the open construct and each construct's range
+    let mut heading = String::new();
+    let mut open: Option<usize> = None;
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for block in blocks {
+        let text = &markdown[block.start..block.end];
+        match &block.kind {
+            BlockKind::Heading { level, text } => {
+                heading = text.clone();
+                if open.is_some_and(|l| *level <= l) {
+                    open = None;
+                    if let Some(last) = ranges.last_mut() {
+                        last.1 = block.start;
+                    }
+                }
+                // Rendered pages may use no-break spaces
+                let text = text.replace('\u{a0}', " ");
+                let Some(c) = construct_re.captures(&text).filter(|_| open.is_none()) else {
+                    continue;
+                };
+                syntax.constructs.push(TemplateConstruct {
+                    id: header_to_slug(c[1].trim()),
+                    name: c[1].trim().to_string(),
+                    syntax: code_re.captures_iter(&c[2]).map(|s| s[1].to_string()).collect(),
+                    description: String::new(),
+                    section_id: leaf_at(&leaves, page_id, block.start),
+                    example: None,
+                });
+                ranges.push((block.start, markdown.len()));
+                open = Some(*level);
+            }
+            BlockKind::Table if heading.contains("Quick Reference") => {
+                for row in table_rows(text) {
+                    let Some(form) = code_re.captures(&row[0]).map(|c| c[1].to_string()) else {
+                        continue;
+                    };
+                    let form = form.replace("&#124;", "|").replace('\u{a0}', " ");
+                    syntax.forms.push(SyntaxForm {
+                        syntax: form.split_whitespace().collect::<Vec<_>>().join(" "),
+                        description: row[1].replace("&#124;", "|").replace('\u{a0}', " "),
+                        construct: None,
+                    });
+                }
+            }
+            BlockKind::Table if heading.contains("Macros") => {
+                for row in table_rows(text).into_iter().filter(|r| r.len() >= 3) {
+                    syntax.macros.push(TemplateMacro {
+                        name: row[0].trim_matches('`').to_string(),
+                        output: plain_text(&row[1]),
+                        use_case: plain_text(&row[2]),
+                    });
+                }
+            }
+            BlockKind::Paragraph if open.is_some() => {
+                let Some(construct) = syntax.constructs.last_mut() else {
+                    continue;
+                };
+                let text = plain_text(text);
+                if construct.description.is_empty()
+                    && !is_code_label(&text)
+                    && !text.starts_with("Generates")
+                {
+                    construct.description = text.trim_end_matches(':').to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    if syntax.forms.is_empty() || syntax.constructs.is_empty() {
+        return None;
+    }
+
+    // Each construct's first Rust example, and the expansion shown right after it
+    let fences = fenced_blocks(markdown);
+    for (construct, (start, end)) in syntax.constructs.iter_mut().zip(&ranges) {
+        let mut inside = fences
+            .iter()
+            .zip(examples)
+            .filter(|(fence, _)| fence.start >= *start && fence.start < *end)
+            .map(|(_, example)| example)
+            .skip_while(|e| e.language != "rust");
+        if let Some(example) = inside.next() {
+            construct.example = Some(TemplateExample {
+                example_id: example.id.clone(),
+                code: example.code.replace('\u{a0}', " "),
+                output: inside
+                    .next()
+                    .filter(|e| e.kind == ExampleKind::Expanded)
+                    .map(|e| e.code.replace('\u{a0}', " ")),
+            });
+        }
+    }
+
+    // A form belongs to the construct whose opening tag it starts with (`{#if` but not
+    // `{#if let` for `{#if cond}`); failing that, the one sharing the longest prefix
+    for form in &mut syntax.forms {
+        let score = |construct: &TemplateConstruct| {
+            construct
+                .syntax
+                .iter()
+                .map(|s| {
+                    let tag = s.split('}').next().unwrap_or(s);
+                    let rest = form.syntax.strip_prefix(tag);
+                    if rest.is_some_and(|r| !r.starts_with(|c: char| c.is_alphanumeric())) {
+                        return 100 + tag.len();
+                    }
+                    if form.syntax.contains(s.as_str()) {
+                        return 50 + s.len();
+                    }
+                    let common = form.syntax.chars().zip(s.chars()).take_while(|(a, b)| a == b);
+                    common.count()
+                })
+                .max()
+                .unwrap_or(0)
+        };
+        let best = syntax.constructs.iter().map(|c| (score(c), c)).filter(|(s, _)| *s >= 2).fold(
+            None,
+            |best: Option<(usize, &TemplateConstruct)>, (s, c)| match best {
+                Some((b, _)) if b >= s => best,
+                _ => Some((s, c)),
+            },
+        );
+        form.construct = best.map(|(_, c)| c.id.clone());
+    }
+
+    Some(syntax)
+}
+
+/// Renders the template grammar as one compact page that fits a single chunk: the
+/// macros, every syntax form, then each construct, with as many of the constructs'
+/// examples as the token budget allows (the rest are in `ts-quote-syntax.json`).
+fn render_template_syntax(syntax: &TemplateSyntax) -> String {
+    (0..=syntax.constructs.len())
+        .rev()
+        .map(|examples| render_template_page(syntax, examples))
+        .find(|md| count_tokens(md) <= CHUNK_TOKEN_BUDGET)
+        .unwrap_or_else(|| render_template_page(syntax, 0))
+}
+
+/// Renders the quick reference with the examples of the first `examples` constructs.
+fn render_template_page(syntax: &TemplateSyntax, examples: usize) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let code = |text: &str| {
+        if text.contains('`') {
+            format!("`` {} ``", text)
+        } else {
+            format!("`{}`", text)
+        }
+    };
+
+    let mut md = format!("# {} Quick Reference\n\n", syntax.title);
+    md.push_str(&format!("{}\n\n", syntax.summary));
+    if !syntax.macros.is_empty() {
+        md.push_str("| Macro | Output | Use Case |\n|-------|--------|----------|\n");
+        for m in &syntax.macros {
+            let (output, use_case) = (cell(&m.output), cell(&m.use_case));
+            md.push_str(&format!("| `{}` | {} | {} |\n", m.name, output, use_case));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Syntax\n\n| Syntax | Description |\n|--------|-------------|\n");
+    for form in &syntax.forms {
+        md.push_str(&format!("| {} | {} |\n", cell(&code(&form.syntax)), cell(&form.description)));
+    }
+
+    md.push_str("\n## Constructs\n");
+    for (i, construct) in syntax.constructs.iter().enumerate() {
+        let forms: Vec<String> = construct.syntax.iter().map(|s| code(s)).collect();
+        md.push_str(&format!("\n### {}: {}\n\n", construct.name, forms.join(", ")));
+        if !construct.description.is_empty() {
+            md.push_str(&format!("{} (`{}`)\n", construct.description, construct.section_id));
+        }
+        if let Some(example) = construct.example.as_ref().filter(|_| i < examples) {
+            md.push_str(&format!("\n```rust\n{}\n```\n", example.code.trim_end()));
+            if let Some(output) = &example.output {
+                md.push_str("\nGenerates:\n\n");
+                md.push_str(&format!("```typescript\n{}\n```\n", output.trim_end()));
+            }
+        }
+    }
+    md
+}
+
+// ============================================================================
+// TypeScript API
+// ============================================================================
+
+/// Category of the sections generated from package type declarations
+const TS_API_CATEGORY: &str = "typescript-api";
+
+/// The exported API of an npm package, read from its `.d.ts` files and written to
+/// `website/static/api-data/typescript/index.json`.
+#[derive(Debug, Serialize)]
+struct ApiPackage {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Id of the package's API section
+    section_id: String,
+    entries: Vec<ApiEntry>,
+}
+
+/// Contents of `website/static/api-data/typescript/index.json`.
+#[derive(Debug, Serialize)]
+struct ApiIndex<'a> {
+    /// Unix time of the extraction, e.g. `1766026211Z`
+    generated: String,
+    packages: &'a [ApiPackage],
+}
+
+/// An exported declaration.
+#[derive(Debug, Serialize)]
+struct ApiEntry {
+    name: String,
+    kind: ApiKind,
+    /// Declaration without `export`/`declare`; only the header for classes and
+    /// interfaces, whose members carry their own signatures
+    signature: String,
+    /// Signatures of the further overloads of a function
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    overloads: Vec<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    doc: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    params: Vec<ApiParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    returns: Option<ApiReturn>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    examples: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    members: Vec<ApiMember>,
+    /// Other declarations of the package the entry's signatures use, e.g. its
+    /// option and result types
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    references: Vec<String>,
+    section_id: String,
+    source_map: SourceMap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ApiKind {
+    Function,
+    Class,
+    Interface,
+    TypeAlias,
+    Enum,
+    Variable,
+}
+
+/// A constructor, method or property of a class or interface, or an enum member.
+#[derive(Debug, Serialize)]
+struct ApiMember {
+    name: String,
+    kind: ApiMemberKind,
+    signature: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    doc: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    params: Vec<ApiParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    returns: Option<ApiReturn>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ApiMemberKind {
+    Constructor,
+    Method,
+    Property,
+    Member,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiParam {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    doc: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiReturn {
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    doc: String,
+}
+
+/// A parsed `/** ... */` comment.
+#[derive(Debug, Default)]
+struct JsDoc {
+    description: String,
+    params: HashMap<String, String>,
+    returns: String,
+    examples: Vec<String>,
+}
+
+/// Directories of the local macroforge packages whose declarations are read:
+/// `node_modules/macroforge` and `node_modules/@macroforge/*`.
+fn api_package_dirs(node_modules: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![node_modules.join("macroforge")];
+    if let Ok(scoped) = fs::read_dir(node_modules.join("@macroforge")) {
+        let mut scoped: Vec<PathBuf> = scoped.flatten().map(|e| e.path()).collect();
+        scoped.sort();
+        dirs.extend(scoped);
+    }
+    dirs.into_iter()
+        .filter(|d| d.join("package.json").exists())
+        .collect()
+}
+
+/// Reads a package's name, version and exported declarations from its
+/// `package.json` and `.d.ts` files.
+fn read_api_package(dir: &Path, repo_root: &Path) -> Option<ApiPackage> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("package.json")).ok()?).ok()?;
+    let name = manifest["name"].as_str()?.to_string();
+    let section_id = format!("{}-api", name.trim_start_matches('@').replace('/', "-"));
+
+    let mut files = Vec::new();
+    collect_declaration_files(dir, &mut files);
+    files.sort();
+
+    let mut entries: Vec<ApiEntry> = Vec::new();
+    for file in files {
+        let Ok(code) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let relative = file
+            .strip_prefix(repo_root)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .to_string();
+        if !declaration_entries(&code, &relative, &mut entries) {
+            eprintln!("Warning: Could not parse type declarations in {}", relative);
+        }
+    }
+
+    let names: Vec<String> = entries.iter().map(|e| e.name.clone()).collect();
+    let word_re = Regex::new(r"\b[A-Za-z_$][\w$]*\b").unwrap();
+    let mut ids: HashSet<String> = HashSet::new();
+    for entry in &mut entries {
+        let mut id = format!("{}/{}", section_id, kebab_case(&entry.name));
+        while !ids.insert(id.clone()) {
+            id.push_str("-2");
+        }
+        entry.section_id = id;
+
+        let text = std::iter::once(entry.signature.as_str())
+            .chain(entry.overloads.iter().map(String::as_str))
+            .chain(entry.members.iter().map(|m| m.signature.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        for word in word_re.find_iter(&text).map(|m| m.as_str()) {
+            let known = names.iter().any(|n| n == word);
+            if known && word != entry.name && !entry.references.iter().any(|r| r == word) {
+                entry.references.push(word.to_string());
+            }
+        }
+    }
+
+    Some(ApiPackage {
+        name,
+        version: manifest["version"].as_str().map(String::from),
+        section_id,
+        entries,
+    })
+}
+
+fn collect_declaration_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(read) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if name != "node_modules" {
+                collect_declaration_files(&path, files);
+            }
+        } else if name.ends_with(".d.ts") || name.ends_with(".d.mts") || name.ends_with(".d.cts") {
+            files.push(path);
+        }
+    }
+}
+
+/// Adds the exported declarations of a `.d.ts` file to `entries`, merging the
+/// overloads of a function into one entry and skipping declarations already read
+/// from another file. Re-exports are not followed; each one is reported. Returns
+/// false if the file does not parse.
+fn declaration_entries(code: &str, file: &str, entries: &mut Vec<ApiEntry>) -> bool {
+    let allocator = Allocator::default();
+    let parsed = TsParser::new(&allocator, code, SourceType::d_ts()).parse();
+    if parsed.panicked || !parsed.errors.is_empty() {
+        return false;
+    }
+
+    let comments = &parsed.program.comments;
+    let doc_at = |start: u32| {
+        comments
+            .iter()
+            .rfind(|c| c.is_jsdoc() && c.attached_to == start)
+            .map(|c| parse_jsdoc(&code[c.span.start as usize..c.span.end as usize]))
+            .unwrap_or_default()
+    };
+    let text = |span: oxc_span::Span| code[span.start as usize..span.end as usize].to_string();
+    let line_of = |offset: u32| code[..offset as usize].matches('\n').count() + 1;
+
+    for statement in &parsed.program.body {
+        let export = match statement {
+            Statement::ExportNamedDeclaration(export) => export,
+            Statement::ExportAllDeclaration(export) => {
+                eprintln!("Warning: Skipping re-export `{}` in {}", text(export.span), file);
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(declaration) = &export.declaration else {
+            if !export.specifiers.is_empty() {
+                let export = text(export.span).split_whitespace().collect::<Vec<_>>().join(" ");
+                eprintln!("Warning: Skipping re-export `{}` in {}", export, file);
+            }
+            continue;
+        };
+        let doc = doc_at(export.span.start);
+        let source_map = SourceMap {
+            file: file.to_string(),
+            lines: Some((line_of(export.span.start), line_of(export.span.end))),
+            node_path: None,
+            headings: Vec::new(),
+        };
+        let mut entry = ApiEntry {
+            name: String::new(),
+            kind: ApiKind::Variable,
+            signature: declaration_signature(&text(declaration.span())),
+            overloads: Vec::new(),
+            doc: doc.description.clone(),
+            params: Vec::new(),
+            returns: None,
+            examples: doc.examples.clone(),
+            members: Vec::new(),
+            references: Vec::new(),
+            section_id: String::new(),
+            source_map,
+        };
+
+        match declaration {
+            Declaration::FunctionDeclaration(function) => {
+                let Some(id) = &function.id else { continue };
+                entry.name = id.name.to_string();
+                entry.kind = ApiKind::Function;
+                entry.params = api_params(code, &function.params, &doc);
+                entry.returns = function.return_type.as_ref().map(|r| ApiReturn {
+                    ty: text(r.type_annotation.span()),
+                    doc: doc.returns.clone(),
+                });
+            }
+            Declaration::ClassDeclaration(class) => {
+                let Some(id) = &class.id else { continue };
+                entry.name = id.name.to_string();
+                entry.kind = ApiKind::Class;
+                entry.signature = declaration_signature(
+                    &code[class.span.start as usize..class.body.span.start as usize],
+                );
+                for element in &class.body.body {
+                    let member_doc = doc_at(element.span().start);
+                    let member = match element {
+                        ClassElement::MethodDefinition(method) => {
+                            let constructor = method.kind.is_constructor();
+                            let function = &method.value;
+                            ApiMember {
+                                name: method
+                                    .key
+                                    .static_name()
+                                    .map_or_else(|| text(method.key.span()), |n| n.to_string()),
+                                kind: if constructor {
+                                    ApiMemberKind::Constructor
+                                } else {
+                                    ApiMemberKind::Method
+                                },
+                                signature: text(method.span),
+                                optional: method.optional,
+                                params: api_params(code, &function.params, &member_doc),
+                                returns: function.return_type.as_ref().map(|r| ApiReturn {
+                                    ty: text(r.type_annotation.span()),
+                                    doc: member_doc.returns.clone(),
+                                }),
+                                doc: member_doc.description,
+                            }
+                        }
+                        ClassElement::PropertyDefinition(property) => ApiMember {
+                            name: property
+                                .key
+                                .static_name()
+                                .map_or_else(|| text(property.key.span()), |n| n.to_string()),
+                            kind: ApiMemberKind::Property,
+                            signature: text(property.span),
+                            optional: property.optional,
+                            doc: member_doc.description,
+                            params: Vec::new(),
+                            returns: None,
+                        },
+                        _ => continue,
+                    };
+                    entry.members.push(member);
+                }
+            }
+            Declaration::TSInterfaceDeclaration(interface) => {
+                entry.name = interface.id.name.to_string();
+                entry.kind = ApiKind::Interface;
+                entry.signature = declaration_signature(
+                    &code[interface.span.start as usize..interface.body.span.start as usize],
+                );
+                for signature in &interface.body.body {
+                    let member_doc = doc_at(signature.span().start);
+                    let member = match signature {
+                        TSSignature::TSPropertySignature(property) => ApiMember {
+                            name: property
+                                .key
+                                .static_name()
+                                .map_or_else(|| text(property.key.span()), |n| n.to_string()),
+                            kind: ApiMemberKind::Property,
+                            signature: text(property.span),
+                            optional: property.optional,
+                            doc: member_doc.description,
+                            params: Vec::new(),
+                            returns: None,
+                        },
+                        TSSignature::TSMethodSignature(method) => ApiMember {
+                            name: method
+                                .key
+                                .static_name()
+                                .map_or_else(|| text(method.key.span()), |n| n.to_string()),
+                            kind: ApiMemberKind::Method,
+                            signature: text(method.span),
+                            optional: method.optional,
+                            params: api_params(code, &method.params, &member_doc),
+                            returns: method.return_type.as_ref().map(|r| ApiReturn {
+                                ty: text(r.type_annotation.span()),
+                                doc: member_doc.returns.clone(),
+                            }),
+                            doc: member_doc.description,
+                        },
+                        _ => continue,
+                    };
+                    entry.members.push(member);
+                }
+            }
+            Declaration::TSTypeAliasDeclaration(alias) => {
+                entry.name = alias.id.name.to_string();
+                entry.kind = ApiKind::TypeAlias;
+            }
+            Declaration::TSEnumDeclaration(e) => {
+                entry.name = e.id.name.to_string();
+                entry.kind = ApiKind::Enum;
+                for member in &e.body.members {
+                    let signature = text(member.span);
+                    entry.members.push(ApiMember {
+                        name: signature.split('=').next().unwrap_or("").trim().to_string(),
+                        kind: ApiMemberKind::Member,
+                        signature,
+                        optional: false,
+                        doc: doc_at(member.span.start).description,
+                        params: Vec::new(),
+                        returns: None,
+                    });
+                }
+            }
+            Declaration::VariableDeclaration(variables) => {
+                let Some(name) = variables
+                    .declarations
+                    .first()
+                    .and_then(|d| d.id.get_identifier_name())
+                else {
+                    continue;
+                };
+                entry.name = name.to_string();
+            }
+            _ => continue,
+        }
+
+        // Packages publishing the same declarations for several module formats
+        // (`index.d.ts` and `index.d.mts`) list each of them once
+        let duplicate = entries.iter().any(|e| {
+            e.kind == entry.kind
+                && e.name == entry.name
+                && (e.signature == entry.signature || e.overloads.contains(&entry.signature))
+        });
+        if duplicate {
+            continue;
+        }
+
+        let overloaded = entries
+            .iter_mut()
+            .find(|e| e.kind == ApiKind::Function && e.kind == entry.kind && e.name == entry.name);
+        match overloaded {
+            Some(existing) => {
+                existing.overloads.push(entry.signature);
+                let same_file = existing.source_map.file == entry.source_map.file;
+                if let (Some(lines), Some((_, end))) =
+                    (existing.source_map.lines.as_mut(), entry.source_map.lines)
+                {
+                    if same_file {
+                        lines.1 = lines.1.max(end);
+                    }
+                }
+                if existing.doc.is_empty() {
+                    existing.doc = entry.doc;
+                }
+                existing.examples.extend(entry.examples);
+            }
+            None => entries.push(entry),
+        }
+    }
+    true
+}
+
+/// Trims a declaration to its signature: no `export`/`declare` keywords, no
+/// trailing `{` and whitespace collapsed.
+fn declaration_signature(text: &str) -> String {
+    let text = text
+        .trim()
+        .trim_end_matches('{')
+        .trim_end_matches(';')
+        .trim();
+    let text = text.strip_prefix("declare ").unwrap_or(text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn api_params(code: &str, params: &oxc_ast::ast::FormalParameters, doc: &JsDoc) -> Vec<ApiParam> {
+    params
+        .items
+        .iter()
+        .filter_map(|param| {
+            let name = param.pattern.get_identifier_name()?.to_string();
+            Some(ApiParam {
+                ty: param.type_annotation.as_ref().map_or_else(
+                    || "any".to_string(),
+                    |t| {
+                        let span = t.type_annotation.span();
+                        code[span.start as usize..span.end as usize].to_string()
+                    },
+                ),
+                optional: param.optional,
+                doc: doc.params.get(&name).cloned().unwrap_or_default(),
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Parses a JSDoc comment into its description and `@param`, `@returns` and
+/// `@example` tags. `{@link X}` becomes `` `X` ``.
+fn parse_jsdoc(comment: &str) -> JsDoc {
+    let link_re = Regex::new(r"\{@link\s+([^}\s|]+)[^}]*\}").unwrap();
+    let param_re =
+        Regex::new(r"^(?:\{[^}]*\}\s*)?\[?([\w$.]+)[^\s\]]*\]?\s*(?:-\s*)?(.*)$").unwrap();
+    let body = comment
+        .trim()
+        .trim_start_matches("/**")
+        .trim_end_matches("*/");
+    let lines: Vec<&str> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+
+    let mut doc = JsDoc::default();
+    let mut description: Vec<&str> = Vec::new();
+    // Current tag and its text so far
+    let mut tag: Option<(&str, Vec<&str>)> = None;
+    let flush = |tag: Option<(&str, Vec<&str>)>, doc: &mut JsDoc| {
+        let Some((name, text)) = tag else { return };
+        match name {
+            "param" => {
+                let text = text.join(" ");
+                if let Some(c) = param_re.captures(text.trim()) {
+                    doc.params
+                        .insert(c[1].to_string(), link_re.replace_all(&c[2], "`$1`").into());
+                }
+            }
+            "returns" | "return" => {
+                let text = text.join(" ");
+                // Drop a leading `{Type}`
+                let text = match text.trim().strip_prefix('{') {
+                    Some(rest) => rest.split_once('}').map_or("", |(_, doc)| doc),
+                    None => text.trim(),
+                };
+                doc.returns = link_re.replace_all(text.trim(), "`$1`").into();
+            }
+            "example" => {
+                let code: Vec<&str> = text
+                    .into_iter()
+                    .filter(|line| !line.trim_start().starts_with("```"))
+                    .collect();
+                let code = code.join("\n").trim().to_string();
+                if !code.is_empty() {
+                    doc.examples.push(code);
+                }
+            }
+            _ => {}
+        }
+    };
+    for line in lines {
+        if let Some(rest) = line.strip_prefix('@') {
+            flush(tag.take(), &mut doc);
+            let (name, text) = rest.split_once(' ').unwrap_or((rest, ""));
+            tag = Some((name, vec![text]));
+        } else if let Some((_, text)) = tag.as_mut() {
+            text.push(line);
+        } else {
+            description.push(line);
+        }
+    }
+    flush(tag, &mut doc);
+
+    let description = description.join("\n");
+    let paragraphs: Vec<String> = description
+        .split("\n\n")
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|p| !p.is_empty())
+        .collect();
+    doc.description = link_re.replace_all(&paragraphs.join("\n\n"), "`$1`").into();
+    doc
+}
+
+/// `expandSync` → `expand-sync`, `NativePlugin` → `native-plugin`, `VERSION` → `version`.
+fn kebab_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut kebab = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_lower)
+            {
+                kebab.push('-');
+            }
+        }
+        kebab.extend(c.to_lowercase());
+    }
+    kebab
+}
+
+/// Title of an entry's section, e.g. `expandSync()` or `NativePlugin`.
+fn api_title(entry: &ApiEntry) -> String {
+    match entry.kind {
+        ApiKind::Function => format!("{}()", entry.name),
+        _ => entry.name.clone(),
+    }
+}
+
+/// Renders the overview section of a package: one row per export.
+fn render_api_package(package: &ApiPackage) -> String {
+    let mut md = format!("# {} API\n\n", package.name);
+    md.push_str(&format!(
+        "TypeScript declarations exported by `{}`",
+        package.name
+    ));
+    if let Some(version) = &package.version {
+        md.push_str(&format!(" v{}", version));
+    }
+    md.push_str(".\n\n| Export | Kind | Description |\n|--------|------|-------------|\n");
+    for entry in &package.entries {
+        let summary = split_sentences(&entry.doc)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        md.push_str(&format!(
+            "| `{}` | {} | {} |\n",
+            api_title(entry),
+            api_kind_label(entry.kind),
+            summary.replace('|', "\\|")
+        ));
+    }
+    md
+}
+
+fn api_kind_label(kind: ApiKind) -> &'static str {
+    match kind {
+        ApiKind::Function => "function",
+        ApiKind::Class => "class",
+        ApiKind::Interface => "interface",
+        ApiKind::TypeAlias => "type alias",
+        ApiKind::Enum => "enum",
+        ApiKind::Variable => "variable",
+    }
+}
+
+/// Renders an entry's section: signature, documentation, parameters, members,
+/// examples, and the members of the interfaces its parameters take.
+fn render_api_entry(package: &ApiPackage, entry: &ApiEntry) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let mut md = format!("# {}\n\n", api_title(entry));
+    md.push_str(&format!(
+        "`{}` {}\n\n",
+        package.name,
+        api_kind_label(entry.kind)
+    ));
+    if !entry.doc.is_empty() {
+        md.push_str(&format!("{}\n\n", entry.doc));
+    }
+    let signatures: Vec<&str> = std::iter::once(entry.signature.as_str())
+        .chain(entry.overloads.iter().map(String::as_str))
+        .collect();
+    md.push_str(&format!("```typescript\n{}\n```\n", signatures.join("\n")));
+
+    if !entry.params.is_empty() {
+        md.push_str("\n## Parameters\n\n| Parameter | Type | Description |\n");
+        md.push_str("|-----------|------|-------------|\n");
+        for param in &entry.params {
+            let optional = if param.optional { " (optional)" } else { "" };
+            md.push_str(&format!(
+                "| `{}`{} | `{}` | {} |\n",
+                param.name,
+                optional,
+                cell(&param.ty),
+                cell(&param.doc)
+            ));
+        }
+    }
+    if let Some(returns) = &entry.returns {
+        md.push_str(&format!("\n## Returns\n\n`{}`", returns.ty));
+        if !returns.doc.is_empty() {
+            md.push_str(&format!(" - {}", returns.doc));
+        }
+        md.push('\n');
+    }
+
+    if !entry.members.is_empty() {
+        md.push_str("\n## Members\n");
+        for member in &entry.members {
+            md.push_str(&format!(
+                "\n### {}\n\n```typescript\n{}\n```\n",
+                member.name, member.signature
+            ));
+            if !member.doc.is_empty() {
+                md.push_str(&format!("\n{}\n", member.doc));
+            }
+        }
+    }
+
+    for example in &entry.examples {
+        md.push_str(&format!(
+            "\n## Example\n\n```typescript\n{}\n```\n",
+            example
+        ));
+    }
+
+    // Option types: interfaces taken as parameters, with their properties
+    let param_types: Vec<&str> = entry
+        .params
+        .iter()
+        .chain(entry.members.iter().flat_map(|m| &m.params))
+        .map(|p| p.ty.as_str())
+        .collect();
+    let option_types: Vec<&ApiEntry> = package
+        .entries
+        .iter()
+        .filter(|e| e.kind == ApiKind::Interface && entry.references.contains(&e.name))
+        .filter(|e| {
+            param_types
+                .iter()
+                .any(|t| t.split(|c: char| !c.is_alphanumeric()).any(|w| w == e.name))
+        })
+        .collect();
+    for option_type in option_types {
+        md.push_str(&format!("\n## {}\n\n", option_type.name));
+        if !option_type.doc.is_empty() {
+            md.push_str(&format!("{}\n\n", option_type.doc));
+        }
+        md.push_str("| Property | Description |\n|----------|-------------|\n");
+        for member in &option_type.members {
+            md.push_str(&format!(
+                "| `{}` | {} |\n",
+                cell(&member.signature),
+                cell(&member.doc)
+            ));
+        }
+    }
+
+    let related: Vec<String> = entry
+        .references
+        .iter()
+        .filter_map(|name| package.entries.iter().find(|e| &e.name == name))
+        .map(|e| format!("`{}` (`{}`)", e.name, e.section_id))
+        .collect();
+    if !related.is_empty() {
+        md.push_str(&format!("\nSee also: {}\n", related.join(", ")));
+    }
+    md
+}
+
+/// Turns the packages into sections: a page per package listing its exports, with
+/// one chunk per export.
+fn api_sections(packages: &[ApiPackage], category_title: &str, stem: bool) -> Vec<DocSection> {
+    let mut sections = Vec::new();
+    for package in packages {
+        let markdown = render_api_package(package);
+        let file = package
+            .entries
+            .first()
+            .map(|e| e.source_map.file.clone())
+            .unwrap_or_default();
+        sections.push(DocSection {
+            id: package.section_id.clone(),
+            title: format!("{} API", package.name),
+            category: TS_API_CATEGORY.to_string(),
+            category_title: category_title.to_string(),
+            path: format!("{}/{}.md", TS_API_CATEGORY, package.section_id),
+            use_cases: String::new(),
+            keywords: configured_keywords("", &markdown, stem),
+            summary: summarize(&markdown),
+            tokens: count_tokens(&markdown),
+            is_chunked: Some(true),
+            chunk_ids: Some(
+                package
+                    .entries
+                    .iter()
+                    .map(|e| e.section_id.clone())
+                    .collect(),
+            ),
+            parent_id: None,
+            source_map: SourceMap {
+                file,
+                lines: None,
+                node_path: None,
+                headings: Vec::new(),
+            },
+            text: markdown,
+        });
+
+        for entry in &package.entries {
+            let markdown = render_api_entry(package, entry);
+            sections.push(DocSection {
+                id: entry.section_id.clone(),
+                title: format!("{}: {}", package.name, api_title(entry)),
+                category: TS_API_CATEGORY.to_string(),
+                category_title: category_title.to_string(),
+                path: format!("{}/{}.md", TS_API_CATEGORY, entry.section_id),
+                use_cases: String::new(),
+                keywords: configured_keywords("", &markdown, stem),
+                summary: summarize(&markdown),
+                tokens: count_tokens(&markdown),
+                is_chunked: None,
+                chunk_ids: None,
+                parent_id: Some(package.section_id.clone()),
+                source_map: SourceMap {
+                    file: entry.source_map.file.clone(),
+                    lines: entry.source_map.lines,
+                    node_path: None,
+                    headings: Vec::new(),
+                },
+                text: markdown,
+            });
+        }
+    }
+    sections
+}
+
+// ============================================================================
+// Rust API
+// ============================================================================
+
+/// Category of the sections generated from rustdoc JSON
+const RUST_API_CATEGORY: &str = "rust-api";
+
+/// A public item of a crate, read from its rustdoc JSON.
+#[derive(Debug)]
+struct RustItem {
+    /// Path below the crate, e.g. `util::indent`
+    path: String,
+    kind: &'static str,
+    signature: String,
+    docs: String,
+    /// Fields, variants, trait items and inherent methods with their docs
+    members: Vec<(String, String)>,
+    /// Traits implemented explicitly (not auto or blanket impls)
+    implements: Vec<String>,
+    source_map: SourceMap,
+}
+
+/// The public API of a crate documented by rustdoc JSON.
+#[derive(Debug)]
+struct RustCrate {
+    name: String,
+    version: Option<String>,
+    docs: String,
+    items: Vec<RustItem>,
+}
+
+/// Reads every rustdoc JSON file (`cargo +nightly rustdoc -- -Z unstable-options
+/// --output-format json`) in `dir`.
+fn read_rustdoc_crates(dir: &Path) -> Vec<RustCrate> {
+    let Ok(read) = fs::read_dir(dir) else {
+        eprintln!("Error: Cannot read rustdoc directory {:?}", dir);
+        std::process::exit(1);
+    };
+    let mut files: Vec<PathBuf> = read
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    files.sort();
+
+    let mut crates = Vec::new();
+    for file in files {
+        let Some(doc) = fs::read_to_string(&file)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .filter(|d| d.get("format_version").is_some() && d["index"].is_object())
+        else {
+            continue;
+        };
+        if let Some(krate) = rustdoc_crate(&doc) {
+            crates.push(krate);
+        } else {
+            eprintln!("Warning: Unsupported rustdoc JSON in {:?}", file);
+        }
+    }
+    crates
+}
+
+fn rustdoc_crate(doc: &serde_json::Value) -> Option<RustCrate> {
+    let index = doc["index"].as_object()?;
+    let item = |id: &serde_json::Value| match id {
+        serde_json::Value::String(id) => index.get(id),
+        id => index.get(&id.to_string()),
+    };
+    let root = item(&doc["root"])?;
+
+    let mut crate_items = Vec::new();
+    // Modules still to walk, with their path below the crate
+    let mut modules = vec![(String::new(), root)];
+    while let Some((prefix, module)) = modules.pop() {
+        let ids = module["inner"]["module"]["items"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for entry in ids.iter().filter_map(&item) {
+            if entry["visibility"] != "public" {
+                continue;
+            }
+            let Some(name) = entry["name"].as_str() else {
+                continue;
+            };
+            let path = format!("{}{}", prefix, name);
+            let Some((kind, inner)) = entry["inner"].as_object().and_then(|o| o.iter().next())
+            else {
+                continue;
+            };
+            if kind == "module" {
+                modules.push((format!("{}::", path), entry));
+                continue;
+            }
+            let generics = rust_generics(&inner["generics"]);
+            let mut members = Vec::new();
+            let mut implements = Vec::new();
+            let (kind, signature) = match kind.as_str() {
+                "struct" => {
+                    let shape = &inner["kind"];
+                    let signature = if shape["tuple"].is_array() {
+                        let fields: Vec<String> = shape["tuple"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|id| item(id).map_or("_".to_string(), rust_field_type))
+                            .collect();
+                        format!("pub struct {}{}({});", name, generics, fields.join(", "))
+                    } else {
+                        format!("pub struct {}{}", name, generics)
+                    };
+                    let fields = shape["plain"]["fields"]
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_default();
+                    for field in fields.iter().filter_map(&item) {
+                        if field["visibility"] == "public" {
+                            members.push(rust_member(
+                                format!(
+                                    "pub {}: {}",
+                                    field["name"].as_str().unwrap_or("_"),
+                                    rust_field_type(field)
+                                ),
+                                field,
+                            ));
+                        }
+                    }
+                    ("struct", signature)
+                }
+                "enum" => {
+                    let variants = inner["variants"].as_array().cloned().unwrap_or_default();
+                    for variant in variants.iter().filter_map(&item) {
+                        let variant_name = variant["name"].as_str().unwrap_or("_");
+                        let shape = &variant["inner"]["variant"]["kind"];
+                        let field_ids = shape["tuple"]
+                            .as_array()
+                            .or(shape["struct"]["fields"].as_array())
+                            .cloned()
+                            .unwrap_or_default();
+                        let fields: Vec<String> = field_ids
+                            .iter()
+                            .filter_map(&item)
+                            .map(|f| match f["name"].as_str() {
+                                Some(name) if shape["struct"].is_object() => {
+                                    format!("{}: {}", name, rust_field_type(f))
+                                }
+                                _ => rust_field_type(f),
+                            })
+                            .collect();
+                        let signature = if shape["tuple"].is_array() {
+                            format!("{}({})", variant_name, fields.join(", "))
+                        } else if shape["struct"].is_object() {
+                            format!("{} {{ {} }}", variant_name, fields.join(", "))
+                        } else {
+                            variant_name.to_string()
+                        };
+                        members.push(rust_member(signature, variant));
+                    }
+                    ("enum", format!("pub enum {}{}", name, generics))
+                }
+                "trait" => {
+                    let bounds = inner["bounds"].as_array().map_or(String::new(), |b| {
+                        if b.is_empty() {
+                            String::new()
+                        } else {
+                            format!(": {}", rust_bounds(b))
+                        }
+                    });
+                    let trait_items = inner["items"].as_array().cloned().unwrap_or_default();
+                    for trait_item in trait_items.iter().filter_map(&item) {
+                        let item_name = trait_item["name"].as_str().unwrap_or("_");
+                        let item_inner = &trait_item["inner"];
+                        let signature = if item_inner["function"].is_object() {
+                            format!("{};", rust_fn_signature(item_name, &item_inner["function"]))
+                        } else if let Some(ty) = item_inner.get("assoc_type") {
+                            match ty["bounds"].as_array().filter(|b| !b.is_empty()) {
+                                Some(b) => format!("type {}: {};", item_name, rust_bounds(b)),
+                                None => format!("type {};", item_name),
+                            }
+                        } else if let Some(constant) = item_inner.get("assoc_const") {
+                            format!("const {}: {};", item_name, rust_type(&constant["type"]))
+                        } else {
+                            continue;
+                        };
+                        members.push(rust_member(signature, trait_item));
+                    }
+                    let unsafety = if inner["is_unsafe"] == true {
+                        "unsafe "
+                    } else {
+                        ""
+                    };
+                    (
+                        "trait",
+                        format!("pub {}trait {}{}{}", unsafety, name, generics, bounds),
+                    )
+                }
+                "function" => (
+                    "function",
+                    format!("pub {}", rust_fn_signature(name, inner)),
+                ),
+                "macro" => ("macro", inner.as_str().unwrap_or_default().to_string()),
+                "proc_macro" => {
+                    let helpers: Vec<String> = inner["helpers"]
+                        .as_array()
+                        .map(|h| {
+                            h.iter()
+                                .filter_map(|h| h.as_str())
+                                .map(String::from)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    match inner["kind"].as_str() {
+                        Some("derive") if helpers.is_empty() => {
+                            ("derive macro", format!("#[derive({})]", name))
+                        }
+                        Some("derive") => (
+                            "derive macro",
+                            format!(
+                                "#[derive({})] // helper attributes: {}",
+                                name,
+                                helpers.join(", ")
+                            ),
+                        ),
+                        Some("attr") => ("attribute macro", format!("#[{}]", name)),
+                        _ => ("macro", format!("{}!(...)", name)),
+                    }
+                }
+                "type_alias" => (
+                    "type alias",
+                    format!(
+                        "pub type {}{} = {};",
+                        name,
+                        generics,
+                        rust_type(&inner["type"])
+                    ),
+                ),
+                "constant" => (
+                    "constant",
+                    format!("pub const {}: {};", name, rust_type(&inner["type"])),
+                ),
+                _ => continue,
+            };
+
+            // Inherent methods and explicitly implemented traits
+            let impls = inner["impls"].as_array().cloned().unwrap_or_default();
+            for implementation in impls.iter().filter_map(&item) {
+                let implementation = &implementation["inner"]["impl"];
+                if implementation["is_synthetic"] == true
+                    || !implementation["blanket_impl"].is_null()
+                {
+                    continue;
+                }
+                if implementation["trait"].is_object() {
+                    implements.push(rust_path(&implementation["trait"]));
+                    continue;
+                }
+                let methods = implementation["items"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default();
+                for method in methods.iter().filter_map(&item) {
+                    let Some(method_name) = method["name"].as_str() else {
+                        continue;
+                    };
+                    if method["visibility"] == "public" && method["inner"]["function"].is_object() {
+                        let signature =
+                            rust_fn_signature(method_name, &method["inner"]["function"]);
+                        members.push(rust_member(format!("pub {}", signature), method));
+                    }
+                }
+            }
+
+            let span = &entry["span"];
+            let line = |key: &str| span[key][0].as_u64().map(|l| l as usize);
+            crate_items.push(RustItem {
+                path,
+                kind,
+                signature,
+                docs: entry["docs"].as_str().unwrap_or_default().to_string(),
+                members,
+                implements,
+                source_map: SourceMap {
+                    file: span["filename"].as_str().unwrap_or_default().to_string(),
+                    lines: line("begin").zip(line("end")),
+                    node_path: None,
+                    headings: Vec::new(),
+                },
+            });
+        }
+    }
+
+    Some(RustCrate {
+        name: root["name"].as_str()?.to_string(),
+        version: doc["crate_version"].as_str().map(String::from),
+        docs: root["docs"].as_str().unwrap_or_default().to_string(),
+        items: crate_items,
+    })
+}
+
+fn rust_member(signature: String, item: &serde_json::Value) -> (String, String) {
+    (
+        signature,
+        item["docs"].as_str().unwrap_or_default().to_string(),
+    )
+}
+
+fn rust_field_type(field: &serde_json::Value) -> String {
+    rust_type(&field["inner"]["struct_field"])
+}
+
+/// Renders a function signature, e.g. `fn field<'a>(&'a self, name: &str) -> Option<&'a Field>`.
+fn rust_fn_signature(name: &str, function: &serde_json::Value) -> String {
+    let sig = if function["sig"].is_object() {
+        &function["sig"]
+    } else {
+        &function["decl"]
+    };
+    let header = &function["header"];
+    let mut qualifiers = String::new();
+    for (flag, keyword) in [
+        ("is_const", "const "),
+        ("is_async", "async "),
+        ("is_unsafe", "unsafe "),
+    ] {
+        if header[flag] == true {
+            qualifiers.push_str(keyword);
+        }
+    }
+    let inputs: Vec<String> = sig["inputs"]
+        .as_array()
+        .map(|inputs| {
+            inputs
+                .iter()
+                .map(|input| {
+                    let name = input[0].as_str().unwrap_or("_");
+                    let ty = &input[1];
+                    if name != "self" {
+                        return format!("{}: {}", name, rust_type(ty));
+                    }
+                    if ty["generic"] == "Self" {
+                        return "self".to_string();
+                    }
+                    match &ty["borrowed_ref"] {
+                        r if r["type"]["generic"] == "Self" => format!(
+                            "&{}{}self",
+                            r["lifetime"]
+                                .as_str()
+                                .map_or(String::new(), |l| format!("{} ", l)),
+                            if r["is_mutable"] == true { "mut " } else { "" }
+                        ),
+                        _ => format!("self: {}", rust_type(ty)),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let output = match &sig["output"] {
+        serde_json::Value::Null => String::new(),
+        ty => format!(" -> {}", rust_type(ty)),
+    };
+    format!(
+        "{}fn {}{}({}){}",
+        qualifiers,
+        name,
+        rust_generics(&function["generics"]),
+        inputs.join(", "),
+        output
+    )
+}
+
+/// Renders generic parameters, e.g. `<'a, S: AsRef<str>>`, leaving out `impl Trait`
+/// arguments.
+fn rust_generics(generics: &serde_json::Value) -> String {
+    let params: Vec<String> = generics["params"]
+        .as_array()
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(|param| {
+                    let name = param["name"].as_str()?;
+                    let kind = &param["kind"];
+                    if kind["type"]["is_synthetic"] == true {
+                        return None;
+                    }
+                    if let Some(bounds) = kind["type"]["bounds"].as_array() {
+                        if !bounds.is_empty() {
+                            return Some(format!("{}: {}", name, rust_bounds(bounds)));
+                        }
+                    }
+                    if kind["const"].is_object() {
+                        return Some(format!(
+                            "const {}: {}",
+                            name,
+                            rust_type(&kind["const"]["type"])
+                        ));
+                    }
+                    Some(name.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn rust_bounds(bounds: &[serde_json::Value]) -> String {
+    bounds
+        .iter()
+        .filter_map(|bound| {
+            if let Some(lifetime) = bound["outlives"].as_str() {
+                return Some(lifetime.to_string());
+            }
+            let bound = &bound["trait_bound"];
+            let maybe = if bound["modifier"] == "maybe" {
+                "?"
+            } else {
+                ""
+            };
+            bound["trait"]
+                .is_object()
+                .then(|| format!("{}{}", maybe, rust_path(&bound["trait"])))
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Renders a path with its generic arguments, e.g. `Result<Self, MacroError>`.
+fn rust_path(path: &serde_json::Value) -> String {
+    let name = path["path"]
+        .as_str()
+        .or(path["name"].as_str())
+        .unwrap_or("_");
+    let name = name.strip_prefix("$crate::").unwrap_or(name);
+    let args = &path["args"];
+    if let Some(angle) = args["angle_bracketed"].as_object() {
+        let mut rendered: Vec<String> = angle["args"]
+            .as_array()
+            .map(|args| {
+                args.iter()
+                    .map(|arg| match arg {
+                        a if a["lifetime"].is_string() => {
+                            a["lifetime"].as_str().unwrap().to_string()
+                        }
+                        a if a.get("type").is_some() => rust_type(&a["type"]),
+                        a => a["const"]["expr"].as_str().unwrap_or("_").to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let constraints = angle.get("constraints").or(angle.get("bindings"));
+        for constraint in constraints.and_then(|c| c.as_array()).into_iter().flatten() {
+            let ty = &constraint["binding"]["equality"]["type"];
+            if !ty.is_null() {
+                let name = constraint["name"].as_str().unwrap_or("_");
+                rendered.push(format!("{} = {}", name, rust_type(ty)));
+            }
+        }
+        if !rendered.is_empty() {
+            return format!("{}<{}>", name, rendered.join(", "));
+        }
+    } else if let Some(parenthesized) = args["parenthesized"].as_object() {
+        let inputs: Vec<String> = parenthesized["inputs"]
+            .as_array()
+            .map(|inputs| inputs.iter().map(rust_type).collect())
+            .unwrap_or_default();
+        let output = match parenthesized.get("output") {
+            Some(ty) if !ty.is_null() => format!(" -> {}", rust_type(ty)),
+            _ => String::new(),
+        };
+        return format!("{}({}){}", name, inputs.join(", "), output);
+    }
+    name.to_string()
+}
+
+/// Renders a rustdoc JSON type.
+fn rust_type(ty: &serde_json::Value) -> String {
+    let Some((kind, inner)) = ty.as_object().and_then(|o| o.iter().next()) else {
+        return "_".to_string();
+    };
+    let mutable = |inner: &serde_json::Value| inner["is_mutable"] == true;
+    match kind.as_str() {
+        "resolved_path" => rust_path(inner),
+        "primitive" | "generic" => inner.as_str().unwrap_or("_").to_string(),
+        "borrowed_ref" => format!(
+            "&{}{}{}",
+            inner["lifetime"]
+                .as_str()
+                .map_or(String::new(), |l| format!("{} ", l)),
+            if mutable(inner) { "mut " } else { "" },
+            rust_type(&inner["type"])
+        ),
+        "raw_pointer" => format!(
+            "*{} {}",
+            if mutable(inner) { "mut" } else { "const" },
+            rust_type(&inner["type"])
+        ),
+        "slice" => format!("[{}]", rust_type(inner)),
+        "array" => format!(
+            "[{}; {}]",
+            rust_type(&inner["type"]),
+            inner["len"].as_str().unwrap_or("_")
+        ),
+        "tuple" => {
+            let types: Vec<String> = inner
+                .as_array()
+                .map(|t| t.iter().map(rust_type).collect())
+                .unwrap_or_default();
+            format!("({})", types.join(", "))
+        }
+        "impl_trait" => format!("impl {}", rust_bounds(inner.as_array().map_or(&[], |b| b))),
+        "dyn_trait" => {
+            let mut traits: Vec<String> = inner["traits"]
+                .as_array()
+                .map(|t| t.iter().map(|t| rust_path(&t["trait"])).collect())
+                .unwrap_or_default();
+            traits.extend(inner["lifetime"].as_str().map(String::from));
+            format!("dyn {}", traits.join(" + "))
+        }
+        "qualified_path" => {
+            let name = inner["name"].as_str().unwrap_or("_");
+            let self_type = rust_type(&inner["self_type"]);
+            match &inner["trait"] {
+                t if t["path"].as_str().is_some_and(|p| !p.is_empty()) => {
+                    format!("<{} as {}>::{}", self_type, rust_path(t), name)
+                }
+                _ => format!("{}::{}", self_type, name),
+            }
+        }
+        "function_pointer" => {
+            let signature = rust_fn_signature("", inner);
+            signature.replacen("fn (", "fn(", 1)
+        }
+        _ => "_".to_string(),
+    }
+}
+
+/// Prepares rustdoc markdown for a section: headings demoted below the item title,
+/// code blocks tagged as Rust with hidden `# ` lines removed, and intra-doc links
+/// (``[`Foo`]``) reduced to their code span.
+fn rustdoc_markdown(docs: &str, levels: usize) -> String {
+    let link_re = Regex::new(r"\[(`[^`\]]+`)\]([^(\[]|$)").unwrap();
+    let mut lines = Vec::new();
+    // Some(is_rust) inside a code block
+    let mut fence: Option<bool> = None;
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            if fence.is_some() {
+                fence = None;
+                lines.push(line.to_string());
+                continue;
+            }
+            let is_rust = info.split(',').all(|attr| {
+                let attr = attr.trim();
+                attr.is_empty()
+                    || attr == "rust"
+                    || attr.starts_with("edition")
+                    || ["ignore", "no_run", "should_panic", "compile_fail"].contains(&attr)
+            });
+            fence = Some(is_rust);
+            lines.push(if is_rust {
+                "```rust".to_string()
+            } else {
+                line.to_string()
+            });
+            continue;
+        }
+        match fence {
+            Some(true) if trimmed == "#" || trimmed.starts_with("# ") => {}
+            Some(_) => lines.push(line.to_string()),
+            None if line.starts_with('#') => {
+                lines.push(format!("{}{}", "#".repeat(levels), line));
+            }
+            None => lines.push(link_re.replace_all(line, "$1$2").into_owned()),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Kebab-case id segment of a Rust path, e.g. `util::indent` → `util-indent`.
+fn rust_slug(path: &str) -> String {
+    path.split("::")
+        .map(|s| kebab_case(s).replace('_', "-"))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn rust_item_title(item: &RustItem) -> String {
+    match item.kind {
+        "function" => format!("{}()", item.path),
+        "macro" => format!("{}!", item.path),
+        _ => item.path.clone(),
+    }
+}
+
+/// Turns the crates into sections: a page per crate with its docs and items, with
+/// one chunk per public item.
+fn rust_api_sections(crates: &[RustCrate], category_title: &str, stem: bool) -> Vec<DocSection> {
+    let mut sections = Vec::new();
+    for krate in crates {
+        let parent_id = format!("{}-rust-api", rust_slug(&krate.name));
+        let mut ids: HashSet<String> = HashSet::new();
+        let item_ids: Vec<String> = krate
+            .items
+            .iter()
+            .map(|item| {
+                let mut id = format!("{}/{}", parent_id, rust_slug(&item.path));
+                while !ids.insert(id.clone()) {
+                    id.push_str("-2");
+                }
+                id
+            })
+            .collect();
+
+        let mut markdown = format!("# {} Rust API\n\n", krate.name);
+        if let Some(version) = &krate.version {
+            markdown.push_str(&format!(
+                "Public API of the `{}` crate v{}.\n\n",
+                krate.name, version
+            ));
+        }
+        if !krate.docs.is_empty() {
+            markdown.push_str(&format!("{}\n\n", rustdoc_markdown(&krate.docs, 1)));
+        }
+        markdown.push_str("| Item | Kind | Description |\n|------|------|-------------|\n");
+        for item in &krate.items {
+            let first_paragraph = item.docs.split("\n\n").next().unwrap_or_default();
+            let summary = split_sentences(&plain_text(&rustdoc_markdown(first_paragraph, 1)))
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            markdown.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                rust_item_title(item),
+                item.kind,
+                summary.replace('|', "\\|")
+            ));
+        }
+        sections.push(DocSection {
+            id: parent_id.clone(),
+            title: format!("{} Rust API", krate.name),
+            category: RUST_API_CATEGORY.to_string(),
+            category_title: category_title.to_string(),
+            path: format!("{}/{}.md", RUST_API_CATEGORY, parent_id),
+            use_cases: String::new(),
+            keywords: configured_keywords("", &markdown, stem),
+            summary: summarize(&markdown),
+            tokens: count_tokens(&markdown),
+            is_chunked: Some(true),
+            chunk_ids: Some(item_ids.clone()),
+            parent_id: None,
+            source_map: SourceMap {
+                file: krate
+                    .items
+                    .first()
+                    .map(|i| i.source_map.file.clone())
+                    .unwrap_or_default(),
+                lines: None,
+                node_path: None,
+                headings: Vec::new(),
+            },
+            text: markdown,
+        });
+
+        for (item, id) in krate.items.iter().zip(item_ids) {
+            let mut markdown = format!("# {}\n\n", rust_item_title(item));
+            markdown.push_str(&format!("`{}` {}\n\n", krate.name, item.kind));
+            markdown.push_str(&format!("```rust\n{}\n```\n", item.signature));
+            if !item.docs.is_empty() {
+                markdown.push_str(&format!("\n{}\n", rustdoc_markdown(&item.docs, 1)));
+            }
+            if !item.members.is_empty() {
+                let heading = match item.kind {
+                    "struct" => "Fields and Methods",
+                    "enum" => "Variants and Methods",
+                    "trait" => "Required Items",
+                    _ => "Members",
+                };
+                markdown.push_str(&format!("\n## {}\n", heading));
+                for (signature, docs) in &item.members {
+                    markdown.push_str(&format!("\n```rust\n{}\n```\n", signature));
+                    if !docs.is_empty() {
+                        markdown.push_str(&format!("\n{}\n", rustdoc_markdown(docs, 2)));
+                    }
+                }
+            }
+            if !item.implements.is_empty() {
+                let traits: Vec<String> =
+                    item.implements.iter().map(|t| format!("`{}`", t)).collect();
+                markdown.push_str(&format!("\nImplements: {}\n", traits.join(", ")));
+            }
+
+            sections.push(DocSection {
+                path: format!("{}/{}.md", RUST_API_CATEGORY, id),
+                id,
+                title: format!("{}: {}", krate.name, rust_item_title(item)),
+                category: RUST_API_CATEGORY.to_string(),
+                category_title: category_title.to_string(),
+                use_cases: String::new(),
+                keywords: configured_keywords("", &markdown, stem),
+                summary: summarize(&markdown),
+                tokens: count_tokens(&markdown),
+                is_chunked: None,
+                chunk_ids: None,
+                parent_id: Some(parent_id.clone()),
+                source_map: SourceMap {
+                    file: item.source_map.file.clone(),
+                    lines: item.source_map.lines,
+                    node_path: None,
+                    headings: Vec::new(),
+                },
+                text: markdown,
+            });
+        }
+    }
+    sections
+}
+
+// ============================================================================
+// Book
+// ============================================================================
+
+const BOOK_TITLE: &str = "Macroforge Documentation";
+const BOOK_TAGLINE: &str = "TypeScript Macros - Rust-Powered Code Generation";
+/// Rule between the pages of the book
+const BOOK_SEPARATOR: &str = "\n---\n\n";
+
+/// A page of `BOOK.md`: a top-level section, or a chunk of a generated API page,
+/// with the anchors of its title and headings.
+struct BookPage<'a> {
+    section: &'a DocSection,
+    anchor: String,
+    /// Level the page's `#` title is demoted to
+    level: usize,
+    /// Headings below the title: level within the page, text and anchor
+    headings: Vec<(usize, String, String)>,
+}
+
+/// Headings of a markdown page outside code blocks, as (line index, level, text).
+fn book_headings(markdown: &str) -> Vec<(usize, usize, String)> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if !in_fence && (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            headings.push((i, level, line[level..].trim().to_string()));
+        }
+    }
+    headings
+}
+
+/// Orders the pages of the book: top-level sections as extracted (navigation order,
+/// with generated pages after the others of their category), with the exports of
+/// the API pages nested below them.
+fn book_pages(sections: &[DocSection]) -> Vec<BookPage<'_>> {
+    let mut top_level: Vec<&DocSection> =
+        sections.iter().filter(|s| s.parent_id.is_none()).collect();
+    let mut categories: Vec<&str> = Vec::new();
+    for section in &top_level {
+        if !categories.contains(&section.category_title.as_str()) {
+            categories.push(&section.category_title);
+        }
+    }
+    top_level.sort_by_key(|s| categories.iter().position(|c| *c == s.category_title));
+
+    let mut used = HashSet::new();
+    let mut pages = Vec::new();
+    for section in top_level {
+        let api = section.category == TS_API_CATEGORY || section.category == RUST_API_CATEGORY;
+        let mut page_sections = vec![(section, 2)];
+        if api {
+            let ids = section.chunk_ids.as_deref().unwrap_or_default();
+            page_sections.extend(
+                ids.iter()
+                    .filter_map(|id| sections.iter().find(|s| &s.id == id))
+                    .map(|s| (s, 3)),
+            );
+        }
+        for (section, level) in page_sections {
+            let anchor = unique_slug(&section.id.replace('/', "-"), &mut used);
+            let headings = book_headings(&section.text)
+                .into_iter()
+                .skip(1)
+                .map(|(_, level, text)| {
+                    let slug = format!("{}-{}", anchor, header_to_slug(&text));
+                    (level, text, unique_slug(&slug, &mut used))
+                })
+                .collect();
+            pages.push(BookPage {
+                section,
+                anchor,
+                level,
+                headings,
+            });
+        }
+    }
+    pages
+}
+
+/// Normalizes a link target to the key of the page it points at:
+/// `../docs/builtin-macros/debug` and `../builtin-macros/debug.md` → `builtin-macros/debug`.
+fn book_link_key(target: &str) -> String {
+    let mut path = target.split('#').next().unwrap_or_default();
+    loop {
+        let stripped = path.trim_start_matches("./").trim_start_matches("../");
+        if stripped == path {
+            break;
+        }
+        path = stripped;
+    }
+    let path = path.trim_start_matches('/');
+    let path = path.strip_prefix("docs/").unwrap_or(path);
+    path.strip_suffix(".md")
+        .unwrap_or(path)
+        .trim_matches('/')
+        .to_string()
+}
+
+/// Renders the book. With `max_tokens`, pages are in full while they fit; a page that
+/// does not fit is reduced to its summary, or left out if even that does not fit, and
+/// later pages still get their chance. The result never exceeds `max_tokens` unless
+/// the title and an empty table of contents alone do.
+fn render_book(
+    sections: &[DocSection],
+    page_ids: &HashMap<String, String>,
+    max_tokens: Option<usize>,
+) -> String {
+    let pages = book_pages(sections);
+
+    // Link keys (navigation hrefs and output paths) → page index
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        targets.insert(book_link_key(&page.section.path), i);
+    }
+    for (href, id) in page_ids {
+        if let Some(i) = pages.iter().position(|p| &p.section.id == id) {
+            targets.insert(book_link_key(href), i);
+        }
+    }
+
+    // Pages included in full (Some(true)), as a summary (Some(false)) or not at all
+    let all_full = vec![Some(true); pages.len()];
+    let Some(max_tokens) = max_tokens else {
+        return assemble_book(&pages, &targets, &all_full, None);
+    };
+
+    // Choose pages by the tokens each adds to the book and to its table of contents
+    let skeleton = assemble_book(&pages, &targets, &vec![None; pages.len()], Some(max_tokens));
+    let mut budget = max_tokens.saturating_sub(count_tokens(&skeleton));
+    let mut included = vec![None; pages.len()];
+    let mut category = None;
+    for (i, page) in pages.iter().enumerate() {
+        let title = &page.section.category_title;
+        let headings = match category == Some(title) {
+            true => 0,
+            false => count_tokens(&format!("# {}\n\n\n### {}\n\n", title, title)),
+        };
+        let cost = |text: String, full: bool| {
+            headings
+                + count_tokens(&format!("{}{}", BOOK_SEPARATOR, text))
+                + count_tokens(&book_toc_entry(page, full))
+        };
+        let full = cost(render_book_page(i, &pages, &targets, &all_full), true);
+        let summary = cost(book_summary(page), false);
+        included[i] = if full <= budget {
+            budget -= full;
+            Some(true)
+        } else if summary <= budget {
+            budget -= summary;
+            Some(false)
+        } else {
+            None
+        };
+        if included[i].is_some() {
+            category = Some(title);
+        }
+    }
+
+    // Token counts of the pieces do not add up exactly, so demote trailing pages
+    // until the rendered book fits
+    let mut book = assemble_book(&pages, &targets, &included, Some(max_tokens));
+    while count_tokens(&book) > max_tokens {
+        let Some(last) = included.iter().rposition(Option::is_some) else {
+            break;
+        };
+        included[last] = match included[last] {
+            Some(true) => Some(false),
+            _ => None,
+        };
+        book = assemble_book(&pages, &targets, &included, Some(max_tokens));
+    }
+    book
+}
+
+/// Puts the book together from the pages chosen by `render_book`.
+fn assemble_book(
+    pages: &[BookPage],
+    targets: &HashMap<String, usize>,
+    included: &[Option<bool>],
+    max_tokens: Option<usize>,
+) -> String {
+    let mut book = format!("# {}\n\n_{}_\n\n---\n\n", BOOK_TITLE, BOOK_TAGLINE);
+    book.push_str(&render_book_toc(pages, included));
+    let mut category = None;
+    for (i, page) in pages.iter().enumerate() {
+        let Some(full) = included[i] else { continue };
+        book.push_str(BOOK_SEPARATOR);
+        if category != Some(&page.section.category_title) {
+            category = Some(&page.section.category_title);
+            book.push_str(&format!("# {}\n\n", page.section.category_title));
+        }
+        if full {
+            book.push_str(&render_book_page(i, pages, targets, included));
+        } else {
+            book.push_str(&book_summary(page));
+        }
+    }
+    let omitted = included.iter().filter(|i| i.is_none()).count();
+    if omitted > 0 {
+        book.push_str(&format!(
+            "{}_{} more pages were left out to stay within {} tokens._\n",
+            BOOK_SEPARATOR,
+            omitted,
+            max_tokens.unwrap_or_default()
+        ));
+    }
+    book
+}
+
+/// An abridged page of the book: its title and summary.
+fn book_summary(page: &BookPage) -> String {
+    format!(
+        "<a id=\"{}\"></a>\n\n{} {}\n\n{}\n\n_Abridged; the full page is `{}`._\n",
+        page.anchor,
+        "#".repeat(page.level),
+        page.section.title,
+        page.section.summary,
+        page.section.path
+    )
+}
+
+/// Renders the nested table of contents: categories, pages, and the top-level
+/// headings of the pages included in full.
+fn render_book_toc(pages: &[BookPage], included: &[Option<bool>]) -> String {
+    let mut toc = String::from("## Table of Contents\n");
+    let mut category = None;
+    for (page, included) in pages.iter().zip(included) {
+        let Some(full) = included else { continue };
+        if category != Some(&page.section.category_title) {
+            category = Some(&page.section.category_title);
+            toc.push_str(&format!("\n### {}\n\n", page.section.category_title));
+        }
+        toc.push_str(&book_toc_entry(page, *full));
+    }
+    toc
+}
+
+/// The table of contents lines of a page: its title, and its top-level headings when
+/// the page is included in full.
+fn book_toc_entry(page: &BookPage, full: bool) -> String {
+    let indent = "  ".repeat(page.level - 2);
+    let mut toc = format!("{}- [{}](#{})\n", indent, page.section.title, page.anchor);
+    if full {
+        for (_, text, anchor) in page.headings.iter().filter(|(level, _, _)| *level == 2) {
+            toc.push_str(&format!("{}  - [{}](#{})\n", indent, text, anchor));
+        }
+    }
+    toc
+}
+
+/// Renders a page in full: headings demoted below the category heading and given
+/// their anchors, and links to other pages of the book rewritten to anchors.
+fn render_book_page(
+    index: usize,
+    pages: &[BookPage],
+    targets: &HashMap<String, usize>,
+    included: &[Option<bool>],
+) -> String {
+    let page = &pages[index];
+    let link_re = Regex::new(r"\]\(([^)\s]+)\)").unwrap();
+    let headings = book_headings(&page.section.text);
+    let resolve = |target: &str| -> Option<String> {
+        if target.contains("://") || target.starts_with("mailto:") {
+            return None;
+        }
+        let page_index = if target.starts_with('#') {
+            Some(index)
+        } else {
+            targets.get(&book_link_key(target)).copied()
+        }?;
+        let target_page = &pages[page_index];
+        let fragment = target.split_once('#').map(|(_, f)| f);
+        match (included[page_index], fragment) {
+            (None, _) => None,
+            (Some(true), Some(fragment)) => Some(
+                target_page
+                    .headings
+                    .iter()
+                    .find(|(_, text, _)| header_to_slug(text) == fragment)
+                    .map_or(&target_page.anchor, |(_, _, anchor)| anchor)
+                    .clone(),
+            ),
+            _ => Some(target_page.anchor.clone()),
+        }
+    };
+
+    let mut markdown = String::new();
+    let mut in_fence = false;
+    let mut next_heading = headings.iter().peekable();
+    let mut heading_anchors =
+        std::iter::once(&page.anchor).chain(page.headings.iter().map(|h| &h.2));
+    for (i, line) in page.section.text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if let Some((_, level, text)) = next_heading.next_if(|(l, _, _)| *l == i) {
+            let anchor = heading_anchors.next().unwrap_or(&page.anchor);
+            let level = (level + page.level - 1).min(6);
+            markdown.push_str(&format!(
+                "<a id=\"{}\"></a>\n\n{} {}\n",
+                anchor,
+                "#".repeat(level),
+                text
+            ));
+        } else if in_fence {
+            markdown.push_str(line);
+            markdown.push('\n');
+        } else {
+            let line =
+                link_re.replace_all(line, |caps: &regex::Captures| match resolve(&caps[1]) {
+                    Some(anchor) => format!("](#{})", anchor),
+                    None => caps[0].to_string(),
+                });
+            markdown.push_str(&line);
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
+
+// ============================================================================
+// llms.txt
+// ============================================================================
+
+const PROJECT_NAME: &str = "Macroforge";
+const PROJECT_SUMMARY: &str = "Macroforge is a Rust-powered macro system for TypeScript: \
+    `@derive` decorators are expanded at compile time into generated methods, with \
+    integrations for Vite, the TypeScript language service, Svelte and Zed.";
+
+/// Renders `llms.txt`: the project summary and a link to every page's markdown,
+/// grouped by navigation category, with the first sentence of its summary.
+fn render_llms_txt(sections: &[DocSection]) -> String {
+    let pages: Vec<BookPage> = book_pages(sections).into_iter().filter(|p| p.level == 2).collect();
+    let mut llms = format!("# {}\n\n> {}\n\n{}\n", PROJECT_NAME, BOOK_TAGLINE, PROJECT_SUMMARY);
+    let mut category = None;
+    for page in &pages {
+        if category != Some(&page.section.category_title) {
+            category = Some(&page.section.category_title);
+            llms.push_str(&format!("\n## {}\n\n", page.section.category_title));
+        }
+        let description = split_sentences(&page.section.summary).into_iter().next();
+        llms.push_str(&format!("- [{}]({})", page.section.title, page.section.path));
+        if let Some(description) = description {
+            llms.push_str(&format!(": {}", description));
+        }
+        llms.push('\n');
+    }
+    llms
+}
+
+/// Renders `llms-full.txt`: the cleaned markdown of every page, in the order of
+/// `BOOK.md`.
+fn render_llms_full_txt(sections: &[DocSection]) -> String {
+    let mut llms = format!("# {}\n\n> {}\n\n{}\n", PROJECT_NAME, BOOK_TAGLINE, PROJECT_SUMMARY);
+    for page in book_pages(sections) {
+        llms.push_str(&format!("\n---\n\nSource: {}\n\n", page.section.path));
+        llms.push_str(page.section.text.trim_end());
+        llms.push('\n');
+    }
+    llms
+}
+
+// ============================================================================
+// Serde Reference
+// ============================================================================
+
+/// Options and validators accepted by the `@serde` decorator, written to `serde.json`
+/// so tooling can check `@serde(...)` usage.
+#[derive(Debug, Serialize)]
+struct SerdeReference {
+    decorator: String,
+    options: Vec<SerdeOption>,
+    validators: Vec<Validator>,
+}
+
+/// A `@serde` option, merged across the macros that document it.
+#[derive(Debug, Serialize)]
+struct SerdeOption {
+    name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    syntax: Vec<String>,
+    params: Vec<Param>,
+    level: OptionLevel,
+    usages: Vec<OptionUsage>,
+}
+
+/// What an option does for one macro, e.g. `skip` for `Serialize`.
+#[derive(Debug, Serialize)]
+struct OptionUsage {
+    #[serde(rename = "macro")]
+    macro_name: String,
+    description: String,
+    section_id: String,
+}
+
+/// A validator usable in `@serde(validate(...))`.
+#[derive(Debug, Serialize)]
+struct Validator {
+    name: String,
+    params: Vec<Param>,
+    /// Field types the validator applies to, from its `<Type> Validators` heading
+    field_types: Vec<String>,
+    category: String,
+    description: String,
+    section_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Param {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    /// Set when some documented form omits the parameter, e.g. `default` / `default = expr`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    variadic: bool,
+}
+
+/// Merges the options of every macro configured by `@serde` and attaches the
+/// validators found on their pages.
+fn build_serde_reference(macros: &[MacroInfo], validators: Vec<Validator>) -> SerdeReference {
+    let mut options: Vec<SerdeOption> = Vec::new();
+    for info in macros.iter().filter(|m| m.decorator.as_deref() == Some("@serde")) {
+        for option in &info.options {
+            let usage = OptionUsage {
+                macro_name: info.name.clone(),
+                description: option.description.clone(),
+                section_id: option.section_id.clone(),
+            };
+            if let Some(existing) = options.iter_mut().find(|o| o.name == option.name) {
+                for alias in &option.aliases {
+                    if !existing.aliases.contains(alias) {
+                        existing.aliases.push(alias.clone());
+                    }
+                }
+                for form in &option.syntax {
+                    if !existing.syntax.contains(form) {
+                        existing.syntax.push(form.clone());
+                    }
+                }
+                existing.params = syntax_params(&existing.syntax);
+                existing.usages.push(usage);
+            } else {
+                options.push(SerdeOption {
+                    name: option.name.clone(),
+                    aliases: option.aliases.clone(),
+                    syntax: option.syntax.clone(),
+                    params: syntax_params(&option.syntax),
+                    level: option.level,
+                    usages: vec![usage],
+                });
+            }
+        }
+    }
+
+    SerdeReference { decorator: "@serde".to_string(), options, validators }
+}
+
+/// Reads every validator named on a ``- `a`, `b(n)` - Description`` list item.
+fn validator_entries(
+    code_re: &Regex,
+    entry: &str,
+    category: &str,
+    section_id: &str,
+) -> Vec<Validator> {
+    let Some((spans, description)) = entry.split_once(" - ") else {
+        return Vec::new();
+    };
+    let field_type = match category.to_lowercase().as_str() {
+        "date" => "Date".to_string(),
+        "array" => "Array".to_string(),
+        other => other.to_string(),
+    };
+
+    code_re
+        .captures_iter(spans)
+        .filter_map(|c| {
+            let form = &c[1];
+            let name = form.split('(').next().unwrap_or("").trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+            Some(Validator {
+                name: name.to_string(),
+                params: form_params(form),
+                field_types: vec![field_type.clone()],
+                category: category.to_string(),
+                description: description.trim().to_string(),
+                section_id: section_id.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parameters of an option from all its documented forms; parameters missing from
+/// some form are optional.
+fn syntax_params(syntax: &[String]) -> Vec<Param> {
+    let forms: Vec<Vec<Param>> = syntax.iter().map(|form| form_params(form)).collect();
+    let Some(longest) = forms.iter().max_by_key(|params| params.len()) else {
+        return Vec::new();
+    };
+    longest
+        .iter()
+        .enumerate()
+        .map(|(i, param)| Param {
+            optional: param.optional || forms.iter().any(|params| params.len() <= i),
+            ..param.clone()
+        })
+        .collect()
+}
+
+/// Parameters of one form: `name(a, b)` or `name = value`. Quoted placeholders are
+/// strings, `n`/`min`/`max` numbers, `expr` an expression and `...` a list of
+/// validators.
+fn form_params(form: &str) -> Vec<Param> {
+    let args: Vec<&str> = if let Some((_, value)) = form.split_once('=') {
+        vec![value.trim()]
+    } else if let Some((_, rest)) = form.split_once('(') {
+        rest.trim_end_matches(')').split(',').map(str::trim).filter(|a| !a.is_empty()).collect()
+    } else {
+        Vec::new()
+    };
+
+    args.into_iter()
+        .map(|arg| {
+            let (name, kind, variadic) = if arg == "..." {
+                ("validators", "validator", true)
+            } else if let Some(quoted) = arg.strip_prefix('"') {
+                (quoted.trim_end_matches('"'), "string", false)
+            } else if matches!(arg, "n" | "min" | "max") {
+                (arg, "number", false)
+            } else if arg == "expr" {
+                (arg, "expression", false)
+            } else {
+                (arg, "unknown", false)
+            };
+            Param { name: name.to_string(), kind: kind.to_string(), optional: false, variadic }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Tokens
+// ============================================================================
+
+/// Counts LLM tokens with the cl100k BPE vocabulary bundled in `tiktoken-rs`, so
+/// budgets work offline and track what the model actually sees for code-heavy pages.
+fn count_tokens(text: &str) -> usize {
+    cl100k_base_singleton().encode_ordinary(text).len()
+}
+
+fn should_chunk(content: &str) -> bool {
+    count_tokens(content) > CHUNK_TOKEN_BUDGET
+}
+
+/// Writes one level of the chunk tree and its section entries, returning the ids of
+/// the chunks at that level. Chunks with children get a chunked entry of their own
+/// (mirroring the page-level parent) plus a full file for reference.
+fn emit_chunks(
+    chunks: &[Chunk],
+    parent_id: &str,
+    trail: &[String],
+    page: &PageInfo,
+    chunk_dir: &Path,
+    options: &ExtractOptions,
+    sections: &mut Vec<DocSection>,
+) -> Vec<String> {
+    let mut chunk_ids = Vec::new();
+
+    for chunk in chunks {
+        let chunk_id = format!("{}/{}", page.item_id, chunk.slug);
+        let chunk_path = chunk_dir.join(format!("{}.md", chunk.slug));
+        let content = with_context(chunk, page, trail, options);
+        write_output(&chunk_path, &content, options);
+
+        let child_ids = if chunk.children.is_empty() {
+            None
+        } else {
+            let mut child_trail = trail.to_vec();
+            child_trail.push(chunk.heading.clone());
+            Some(emit_chunks(
+                &chunk.children,
+                &chunk_id,
+                &child_trail,
+                page,
+                chunk_dir,
+                options,
+                sections,
+            ))
+        };
+
+        sections.push(DocSection {
+            id: chunk_id.clone(),
+            title: chunk.title.clone(),
+            category: page.category.clone(),
+            category_title: page.category_title.clone(),
+            path: format!("{}/{}/{}.md", page.category, page.item_id, chunk.slug),
+            use_cases: String::new(),
+            keywords: page.keywords.iter().take(INHERITED_KEYWORDS).cloned().collect(),
+            summary: summarize(&chunk.content),
+            tokens: count_tokens(&content),
+            is_chunked: child_ids.as_ref().map(|_| true),
+            chunk_ids: child_ids,
+            parent_id: Some(parent_id.to_string()),
+            source_map: source_map(page.source, page.blocks, chunk.start, chunk.end),
+            text: chunk.content.clone(),
+        });
+
+        chunk_ids.push(chunk_id);
+    }
+
+    chunk_ids
+}
+
+// ============================================================================
+// Source Mapping
+// ============================================================================
+
+/// Maps the markdown byte range `start..end` of a page back to its source.
+fn source_map(source: &PageSource, blocks: &[Block], start: usize, end: usize) -> SourceMap {
+    let text = &source.markdown[start..end];
+    let start = start + (text.len() - text.trim_start().len());
+    let end = (start + text.trim().len()).max(start + 1);
+    let headings = blocks.iter().filter(|b| b.start >= start && b.start < end);
+
+    match &source.origin {
+        SourceOrigin::Mdsvex { offsets, line_starts } => {
+            let line_of = |offset: usize| line_starts.partition_point(|&s| s <= offsets[offset]);
+            SourceMap {
+                file: source.file.clone(),
+                lines: Some((line_of(start), line_of(end - 1))),
+                node_path: None,
+                headings: headings
+                    .filter_map(|b| match &b.kind {
+                        BlockKind::Heading { level, text } => Some(HeadingSource {
+                            level: *level,
+                            text: text.clone(),
+                            line: Some(line_of(b.start)),
+                            node_path: None,
+                        }),
+                        _ => None,
+                    })
+                    .collect(),
+            }
+        }
+        SourceOrigin::Prerendered { root_path, headings: html_headings } => {
+            let paths = match_heading_paths(blocks, html_headings);
+            let headings: Vec<HeadingSource> = headings
+                .filter_map(|b| match &b.kind {
+                    BlockKind::Heading { level, text } => Some(HeadingSource {
+                        level: *level,
+                        text: text.clone(),
+                        line: None,
+                        node_path: paths.get(&b.start).cloned(),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            SourceMap {
+                file: source.file.clone(),
+                lines: None,
+                node_path: headings
+                    .first()
+                    .and_then(|h| h.node_path.clone())
+                    .or_else(|| Some(root_path.clone())),
+                headings,
+            }
+        }
+    }
+}
+
+/// Pairs markdown heading blocks with prerendered HTML headings, in document order,
+/// by comparing their text. Returns node paths keyed by heading block start.
+fn match_heading_paths(
+    blocks: &[Block],
+    html_headings: &[(String, String)],
+) -> HashMap<usize, String> {
+    let mut paths = HashMap::new();
+    let mut next = 0;
+    for block in blocks {
+        let BlockKind::Heading { text, .. } = &block.kind else {
+            continue;
+        };
+        let text = text.replace('`', "");
+        if let Some(offset) = html_headings[next..].iter().position(|(t, _)| *t == text) {
+            paths.insert(block.start, html_headings[next + offset].1.clone());
+            next += offset + 1;
+        }
+    }
+    paths
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Removes the context header added by `with_context`, leaving the source text.
+fn strip_context(content: &str) -> &str {
+    if let Some(rest) = content.strip_prefix(CONTEXT_START) {
+        if let Some(end) = rest.find(CONTEXT_END) {
+            return rest[end + CONTEXT_END.len()..].trim_start();
+        }
+    }
+    content
+}
+
+/// Collects the leaf chunks in document order, as they are written to disk.
+fn emitted_leaves(
+    chunks: &[Chunk],
+    trail: &[String],
+    page: &PageInfo,
+    options: &ExtractOptions,
+    out: &mut Vec<String>,
+) {
+    for chunk in chunks {
+        if chunk.children.is_empty() {
+            out.push(with_context(chunk, page, trail, options));
+        } else {
+            let mut child_trail = trail.to_vec();
+            child_trail.push(chunk.heading.clone());
+            emitted_leaves(&chunk.children, &child_trail, page, options, out);
+        }
+    }
+}
+
+/// Rebuilds a page from its emitted leaf chunks (minus context headers) and diffs it
+/// against the original markdown. Dropped, duplicated or reordered text is an error;
+/// differences in blank lines between blocks are not.
+fn verify_lossless(
+    markdown: &str,
+    chunks: &[Chunk],
+    page: &PageInfo,
+    options: &ExtractOptions,
+) -> Result<(), String> {
+    let mut leaves = Vec::new();
+    emitted_leaves(chunks, &[], page, options, &mut leaves);
+    let rebuilt = leaves.iter().map(|c| strip_context(c)).collect::<Vec<_>>().join("\n\n");
+
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalize(markdown) == normalize(&rebuilt) {
+        return Ok(());
+    }
+
+    let block_texts = |text: &str| -> Vec<String> {
+        parse_blocks(text).iter().map(|b| normalize(&text[b.start..b.end])).collect()
+    };
+    let original = block_texts(markdown);
+    let rebuilt_blocks = block_texts(&rebuilt);
+
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for block in &original {
+        counts.entry(block).or_default().0 += 1;
+    }
+    for block in &rebuilt_blocks {
+        counts.entry(block).or_default().1 += 1;
+    }
+
+    let mut problems = Vec::new();
+    for block in &original {
+        if let Some((expected, actual)) = counts.remove(block.as_str()) {
+            if actual < expected {
+                problems.push(format!("  dropped: {}", excerpt(block)));
+            } else if actual > expected {
+                problems.push(format!("  duplicated: {}", excerpt(block)));
+            }
+        }
+    }
+    for (block, (expected, _)) in counts {
+        if expected == 0 {
+            problems.push(format!("  unexpected: {}", excerpt(block)));
+        }
+    }
+
+    if problems.is_empty() {
+        match original.iter().zip(&rebuilt_blocks).position(|(a, b)| a != b) {
+            Some(i) => {
+                problems.push(format!("  reordered at block {}: {}", i + 1, excerpt(&original[i])))
+            }
+            // Every block matches, so the difference is text outside any block
+            // (e.g. link reference definitions between chunks)
+            None => problems.push("  text outside markdown blocks was dropped".to_string()),
+        }
+    }
+
+    Err(problems.join("\n"))
+}
+
+fn excerpt(text: &str) -> String {
+    match text.char_indices().nth(80) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+/// Checks that no two sections share an id or an output path. A duplicate path means
+/// one markdown file silently overwrote another.
+fn validate_unique(sections: &[DocSection]) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let mut ids: HashMap<&str, &str> = HashMap::new();
+    let mut paths: HashMap<&str, &str> = HashMap::new();
+
+    for section in sections {
+        if let Some(other) = ids.insert(&section.id, &section.title) {
+            errors.push(format!(
+                "duplicate section id '{}' ('{}' and '{}')",
+                section.id, other, section.title
+            ));
+        }
+        if let Some(other) = paths.insert(&section.path, &section.id) {
+            errors.push(format!(
+                "duplicate output path '{}' ('{}' and '{}')",
+                section.path, other, section.id
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+/// Writes an output file, creating parent directories. Does nothing in check mode.
+fn write_output(path: &Path, content: &str, options: &ExtractOptions) {
+    if options.check {
+        return;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).ok();
+    }
+    fs::write(path, content).ok();
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+fn main() {
+    let options = parse_options();
+
+    // Paths - script runs from repo root or from packages/mcp-server
+    let cwd = std::env::current_dir().unwrap();
+
+    // Determine repo root - check for pixi.toml to confirm we're at root
+    let repo_root = if cwd.join("pixi.toml").exists() {
+        cwd.clone()
+    } else {
+        // Try to find repo root by looking for pixi.toml
+        let mut candidate = cwd.clone();
+        loop {
+            if candidate.join("pixi.toml").exists() {
+                break candidate;
+            }
+            match candidate.parent() {
+                Some(parent) => candidate = parent.to_path_buf(),
+                None => break cwd.clone(), // Give up and use cwd
+            }
+        }
+    };
+
+    let website_dir = repo_root.join("website");
+    let prerendered_dir = website_dir.join("build/prerendered");
+    let navigation_path = website_dir.join("src/lib/config/navigation.ts");
+    let output_dir = repo_root.join("packages/mcp-server/docs");
+
+    // Check prerendered exists
+    let has_prerendered = prerendered_dir.exists();
+    if !has_prerendered {
+        eprintln!("Warning: Prerendered directory not found: {:?}", prerendered_dir);
+        eprintln!("Falling back to mdsvex source pages when available.\n");
+    }
+
+    println!("Auto-discovering pages from navigation.ts...\n");
+
+    // Parse navigation
+    let navigation = parse_navigation(&navigation_path);
+    println!("Found {} sections in navigation.ts\n", navigation.len());
+
+    // Ensure output directory exists
+    if !options.check {
+        if let Err(e) = fs::create_dir_all(&output_dir) {
+            eprintln!("Failed to create output directory: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let use_cases_map = get_use_cases_map();
+    let category_id_map = get_category_id_map();
+    let page_ids = assign_page_ids(&navigation, &category_id_map);
+    let mut sections: Vec<DocSection> = Vec::new();
+    let mut checked_pages = 0;
+    let mut failed_pages: Vec<String> = Vec::new();
+    let mut examples: Vec<CodeExample> = Vec::new();
+    let mut macro_catalog = MacroCatalog::default();
+    let mut cli_commands: Vec<CliCommand> = Vec::new();
+    let mut errors: Vec<ErrorEntry> = Vec::new();
+    let mut template_syntax: Option<TemplateSyntax> = None;
+    // Id, summary and documented options of the page describing macroforge.json
+    let mut config_page: Option<(String, String, Vec<ConfigOption>)> = None;
+
+    for section in &navigation {
+        let category = href_to_category(section.items.first().map(|i| i.href.as_str()).unwrap_or(""));
+
+        let category_dir = output_dir.join(&category);
+
+        for item in &section.items {
+            let item_id = page_ids[&item.href].clone();
+            println!("Processing: {} ({})", item.title, item.href);
+
+            // Try source markdown first
+            let page_source = match read_markdown_source(&item.href, &website_dir, &repo_root) {
+                Some(source) => source,
+                None => {
+                    if !has_prerendered {
+                        eprintln!("Warning: No source markdown for {} and no prerendered HTML", item.href);
+                        continue;
+                    }
+
+                    let html_path = href_to_prerendered_path(&item.href, &website_dir);
+                    if !html_path.exists() {
+                        eprintln!("Warning: File not found: {:?}", html_path);
+                        continue;
+                    }
+
+                    read_prerendered_page(&html_path, &repo_root)
+                }
+            };
+            let markdown_content = &page_source.markdown;
+            let page_summary = summarize(markdown_content);
+            let page_blocks = parse_blocks(markdown_content);
+            let page_source_map =
+                || source_map(&page_source, &page_blocks, 0, markdown_content.len());
+
+            let keywords = configured_keywords(
+                use_cases_map.get(item.href.as_str()).copied().unwrap_or(""),
+                markdown_content,
+                options.stem,
+            );
+
+            // Check if we need to chunk
+            if should_chunk(markdown_content) {
+                let page = PageInfo {
+                    item_id: item_id.clone(),
+                    title: item.title.clone(),
+                    category: category.clone(),
+                    category_title: section.title.clone(),
+                    keywords: keywords.clone(),
+                    summary: page_summary.clone(),
+                    source: &page_source,
+                    blocks: &page_blocks,
+                };
+                let chunks = match chunk_markdown(&page, &options) {
+                    Ok(chunks) => chunks,
+                    Err(error) => {
+                        eprintln!("Error: {} ({})", error, item.href);
+                        std::process::exit(1);
+                    }
+                };
+
+                if is_split(&chunks) {
+                    println!("  → Chunking into {} parts", chunks.len());
+
+                    let chunk_dir = category_dir.join(&item_id);
+                    let chunk_ids = emit_chunks(
+                        &chunks,
+                        &item_id,
+                        &[],
+                        &page,
+                        &chunk_dir,
+                        &options,
+                        &mut sections,
+                    );
+                    let found = page_examples(&item_id, &page_source, &chunks);
+                    cli_commands.extend(page_cli_commands(
+                        &item_id,
+                        &page_source,
+                        &page_blocks,
+                        &chunks,
+                        &found,
+                    ));
+                    errors.extend(page_errors(
+                        &item_id,
+                        &page_source,
+                        &page_blocks,
+                        &chunks,
+                        &found,
+                    ));
+                    if category == "custom-macros" {
+                        template_syntax = template_syntax.or(page_template_syntax(
+                            &item_id,
+                            &item.title,
+                            &page_source,
+                            &page_blocks,
+                            &chunks,
+                            &found,
+                        ));
+                    }
+                    if is_config_page(&found) {
+                        let options =
+                            page_config_options(&item_id, &page_source, &page_blocks, &chunks);
+                        config_page = Some((item_id.clone(), page_summary.clone(), options));
+                    }
+                    examples.extend(found);
+                    if category == "builtin-macros" {
+                        catalog_macro_page(
+                            &mut macro_catalog,
+                            &item_id,
+                            &item.title,
+                            &page_summary,
+                            &page_source,
+                            &page_blocks,
+                            &chunks,
+                        );
+                    }
+
+                    // Add parent entry
+                    sections.push(DocSection {
+                        id: item_id.clone(),
+                        title: item.title.clone(),
+                        category: category.clone(),
+                        category_title: section.title.clone(),
+                        path: format!("{}/{}.md", category, item_id),
+                        use_cases: String::new(),
+                        keywords,
+                        summary: page_summary.clone(),
+                        tokens: count_tokens(markdown_content),
+                        is_chunked: Some(true),
+                        chunk_ids: Some(chunk_ids),
+                        parent_id: None,
+                        source_map: page_source_map(),
+                        text: markdown_content.clone(),
+                    });
+
+                    // Write full file for reference
+                    let output_path = category_dir.join(format!("{}.md", item_id));
+                    write_output(&output_path, markdown_content, &options);
+
+                    if options.check {
+                        checked_pages += 1;
+                        if let Err(report) =
+                            verify_lossless(markdown_content, &chunks, &page, &options)
+                        {
+                            eprintln!("Lossless check failed for {}:\n{}", item.href, report);
+                            failed_pages.push(item.href.clone());
+                        }
+                    }
+
+                    continue;
+                }
+            }
+
+            // Not chunked - write as single file
+            let found = page_examples(&item_id, &page_source, &[]);
+            cli_commands.extend(page_cli_commands(
+                &item_id,
+                &page_source,
+                &page_blocks,
+                &[],
+                &found,
+            ));
+            errors.extend(page_errors(&item_id, &page_source, &page_blocks, &[], &found));
+            if category == "custom-macros" {
+                template_syntax = template_syntax.or(page_template_syntax(
+                    &item_id,
+                    &item.title,
+                    &page_source,
+                    &page_blocks,
+                    &[],
+                    &found,
+                ));
+            }
+            if is_config_page(&found) {
+                let options = page_config_options(&item_id, &page_source, &page_blocks, &[]);
+                config_page = Some((item_id.clone(), page_summary.clone(), options));
+            }
+            examples.extend(found);
+            if category == "builtin-macros" {
+                catalog_macro_page(
+                    &mut macro_catalog,
+                    &item_id,
+                    &item.title,
+                    &page_summary,
+                    &page_source,
+                    &page_blocks,
+                    &[],
+                );
+            }
+            let output_path = category_dir.join(format!("{}.md", item_id));
+            write_output(&output_path, markdown_content, &options);
 
             sections.push(DocSection {
-                id: item_id,
+                id: item_id.clone(),
                 title: item.title.clone(),
                 category: category.clone(),
                 category_title: section.title.clone(),
-                path: format!("{}/{}.md", category, item.href.split('/').last().unwrap_or("")),
-                use_cases,
+                path: format!("{}/{}.md", category, item_id),
+                use_cases: String::new(),
+                keywords,
+                summary: page_summary,
+                tokens: count_tokens(markdown_content),
                 is_chunked: None,
                 chunk_ids: None,
                 parent_id: None,
+                source_map: page_source_map(),
+                text: markdown_content.clone(),
             });
         }
     }
 
+    // Add the macro × target support matrix as a section of its own
+    let support_matrix = build_support_matrix(&macro_catalog, &examples);
+    if !support_matrix.is_empty() {
+        let category = "builtin-macros";
+        let category_title = navigation
+            .iter()
+            .find(|s| s.items.first().is_some_and(|i| href_to_category(&i.href) == category))
+            .map_or_else(|| "Built-in Macros".to_string(), |s| s.title.clone());
+        let markdown = render_support_matrix(&support_matrix, &macro_catalog.support);
+        let source_file = macro_catalog
+            .support
+            .iter()
+            .map(|s| s.section_id.as_str())
+            .chain(support_matrix.iter().map(|m| m.section_id.as_str()))
+            .find_map(|id| sections.iter().find(|s| s.id == id))
+            .map(|s| s.source_map.file.clone())
+            .unwrap_or_default();
+
+        let output_path = output_dir.join(category).join(format!("{}.md", SUPPORT_SECTION_ID));
+        write_output(&output_path, &markdown, &options);
+        sections.push(DocSection {
+            id: SUPPORT_SECTION_ID.to_string(),
+            title: "Macro Target Support".to_string(),
+            category: category.to_string(),
+            category_title,
+            path: format!("{}/{}.md", category, SUPPORT_SECTION_ID),
+            use_cases: String::new(),
+            keywords: configured_keywords("", &markdown, options.stem),
+            summary: summarize(&markdown),
+            tokens: count_tokens(&markdown),
+            is_chunked: None,
+            chunk_ids: None,
+            parent_id: None,
+            source_map: SourceMap {
+                file: source_file,
+                lines: None,
+                node_path: None,
+                headings: Vec::new(),
+            },
+            text: markdown,
+        });
+    }
+
+    // Add the ts_template! grammar as a quick reference that fits a single chunk
+    if let Some(syntax) = &template_syntax {
+        let category = "custom-macros";
+        let category_title = navigation
+            .iter()
+            .find(|s| s.items.first().is_some_and(|i| href_to_category(&i.href) == category))
+            .map_or_else(|| "Custom Macros".to_string(), |s| s.title.clone());
+        let markdown = render_template_syntax(syntax);
+        let source_file = sections
+            .iter()
+            .find(|s| s.id == syntax.section_id)
+            .map(|s| s.source_map.file.clone())
+            .unwrap_or_default();
+
+        let file_name = format!("{}.md", TEMPLATE_SYNTAX_SECTION_ID);
+        write_output(&output_dir.join(category).join(&file_name), &markdown, &options);
+        sections.push(DocSection {
+            id: TEMPLATE_SYNTAX_SECTION_ID.to_string(),
+            title: format!("{} Quick Reference", syntax.title),
+            category: category.to_string(),
+            category_title,
+            path: format!("{}/{}", category, file_name),
+            use_cases: String::new(),
+            keywords: configured_keywords("", &markdown, options.stem),
+            summary: summarize(&markdown),
+            tokens: count_tokens(&markdown),
+            is_chunked: None,
+            chunk_ids: None,
+            parent_id: None,
+            source_map: SourceMap {
+                file: source_file,
+                lines: None,
+                node_path: None,
+                headings: Vec::new(),
+            },
+            text: markdown,
+        });
+    }
+
+    // Add the exported API of the local macroforge packages, one chunk per export
+    let api_packages: Vec<ApiPackage> = api_package_dirs(&repo_root.join("node_modules"))
+        .iter()
+        .filter_map(|dir| read_api_package(dir, &repo_root))
+        .filter(|package| !package.entries.is_empty())
+        .collect();
+    for section in api_sections(&api_packages, "TypeScript API", options.stem) {
+        write_output(&output_dir.join(&section.path), &section.text, &options);
+        sections.push(section);
+    }
+
+    // Add the public items of the crates documented by rustdoc JSON, one chunk per item
+    if let Some(rustdoc_dir) = &options.rustdoc_dir {
+        let crates = read_rustdoc_crates(rustdoc_dir);
+        if crates.is_empty() {
+            eprintln!("Warning: No rustdoc JSON found in {:?}", rustdoc_dir);
+        }
+        for section in rust_api_sections(&crates, "Rust API", options.stem) {
+            write_output(&output_dir.join(&section.path), &section.text, &options);
+            sections.push(section);
+        }
+    }
+
+    if let Err(errors) = validate_unique(&sections) {
+        for error in errors {
+            eprintln!("Error: {}", error);
+        }
+        std::process::exit(1);
+    }
+
+    assign_keywords(&mut sections, options.stem);
+
+    if options.check {
+        if !failed_pages.is_empty() {
+            eprintln!(
+                "\nLossless check failed for {} of {} chunked pages",
+                failed_pages.len(),
+                checked_pages
+            );
+            std::process::exit(1);
+        }
+        println!("\nLossless check passed for {} chunked pages", checked_pages);
+        return;
+    }
+
     // Write sections.json
     let sections_path = output_dir.join("sections.json");
     let json = serde_json::to_string_pretty(&sections).unwrap();
-    fs::write(&sections_path, json).ok();
+    write_output(&sections_path, &json, &options);
+
+    // Write search-index.json
+    let index_path = output_dir.join("search-index.json");
+    let index = serde_json::to_string(&build_search_index(&sections)).unwrap();
+    write_output(&index_path, &index, &options);
+
+    // Write vectors.json
+    let vectors_path = output_dir.join("vectors.json");
+    let vectors = serde_json::to_string(&build_section_vectors(&sections)).unwrap();
+    write_output(&vectors_path, &vectors, &options);
+
+    // Write aliases.json
+    let aliases_path = output_dir.join("aliases.json");
+    let aliases = build_alias_dictionary(&navigation, &page_ids, &sections);
+    let aliases = serde_json::to_string_pretty(&aliases).unwrap();
+    write_output(&aliases_path, &aliases, &options);
+
+    // Write BOOK.md, and BOOK-capped.md when a budget is given
+    let book_path = output_dir.join("BOOK.md");
+    write_output(&book_path, &render_book(&sections, &page_ids, None), &options);
+    if let Some(max_tokens) = options.book_max_tokens {
+        let book = render_book(&sections, &page_ids, Some(max_tokens));
+        write_output(&output_dir.join("BOOK-capped.md"), &book, &options);
+    }
+
+    // Write llms.txt and llms-full.txt
+    if options.llms {
+        write_output(&output_dir.join("llms.txt"), &render_llms_txt(&sections), &options);
+        let llms_full = render_llms_full_txt(&sections);
+        write_output(&output_dir.join("llms-full.txt"), &llms_full, &options);
+    }
+
+    // Write example-pairs.json, linking the paired entries of examples.json
+    let pairs = pair_examples(&mut examples);
+    let pairs_path = output_dir.join("example-pairs.json");
+    let json = serde_json::to_string_pretty(&pairs).unwrap();
+    write_output(&pairs_path, &json, &options);
+
+    // Write examples.json
+    let examples_path = output_dir.join("examples.json");
+    let json = serde_json::to_string_pretty(&examples).unwrap();
+    write_output(&examples_path, &json, &options);
+
+    // Write macros.json
+    let macros_path = output_dir.join("macros.json");
+    let validators = std::mem::take(&mut macro_catalog.validators);
+    let macros = finish_macro_catalog(macro_catalog, &pairs);
+    let json = serde_json::to_string_pretty(&macros).unwrap();
+    write_output(&macros_path, &json, &options);
+
+    // Write macro-support.json
+    let support_path = output_dir.join("macro-support.json");
+    let json = serde_json::to_string_pretty(&support_matrix).unwrap();
+    write_output(&support_path, &json, &options);
+
+    // Write cli.json
+    let cli_path = output_dir.join("cli.json");
+    let json = serde_json::to_string_pretty(&cli_commands).unwrap();
+    write_output(&cli_path, &json, &options);
+
+    // Write ts-quote-syntax.json
+    if let Some(syntax) = &template_syntax {
+        let syntax_path = output_dir.join("ts-quote-syntax.json");
+        let json = serde_json::to_string_pretty(syntax).unwrap();
+        write_output(&syntax_path, &json, &options);
+    }
+
+    // Write website/static/api-data/typescript/index.json
+    if api_packages.is_empty() {
+        eprintln!("Warning: No macroforge type declarations found under node_modules");
+    } else {
+        let api_index_path =
+            repo_root.join("packages/mcp-server/website/static/api-data/typescript/index.json");
+        let generated = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let index = ApiIndex { generated: format!("{}Z", generated), packages: &api_packages };
+        let mut json = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut json, formatter);
+        index.serialize(&mut serializer).unwrap();
+        write_output(&api_index_path, &String::from_utf8(json).unwrap(), &options);
+    }
+
+    // Write errors.json
+    let errors_path = output_dir.join("errors.json");
+    let errors = finish_error_catalog(errors, &macros);
+    let json = serde_json::to_string_pretty(&errors).unwrap();
+    write_output(&errors_path, &json, &options);
+
+    // Write macroforge.schema.json and config-report.json
+    if let Some((page_id, summary, config_options)) = &config_page {
+        let prefix = format!("{}/", page_id);
+        let json_examples: Vec<&CodeExample> = examples
+            .iter()
+            .filter(|e| &e.section_id == page_id || e.section_id.starts_with(&prefix))
+            .filter(|e| e.language == "json" || e.language == "jsonc")
+            .collect();
+        let (schema, report) = build_config_schema(summary, config_options, &json_examples);
+        for entry in &report.undocumented {
+            eprintln!(
+                "Warning: {} key `{}` is used in examples but never documented",
+                CONFIG_FILE_NAME, entry.key
+            );
+        }
+        for entry in &report.unused {
+            eprintln!(
+                "Warning: {} key `{}` is documented but never used in an example",
+                CONFIG_FILE_NAME, entry.key
+            );
+        }
+
+        let schema_path = output_dir.join("macroforge.schema.json");
+        let json = serde_json::to_string_pretty(&schema).unwrap();
+        write_output(&schema_path, &json, &options);
+        let report_path = output_dir.join("config-report.json");
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        write_output(&report_path, &json, &options);
+    }
+
+    // Write serde.json
+    let serde_path = output_dir.join("serde.json");
+    let reference = build_serde_reference(&macros, validators);
+    let json = serde_json::to_string_pretty(&reference).unwrap();
+    write_output(&serde_path, &json, &options);
 
     println!("\nExtracted {} documentation sections", sections.len());
     println!("Output directory: {:?}", output_dir);
 }
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A paragraph of roughly `sentences * 10` tokens.
+    fn paragraph(sentences: usize) -> String {
+        vec!["The quick brown fox jumps over the lazy dog."; sentences].join(" ")
+    }
+
+    /// Every chunk of the tree, parents before their children.
+    fn all_chunks(chunks: &[Chunk]) -> Vec<&Chunk> {
+        chunks.iter().flat_map(|c| std::iter::once(c).chain(all_chunks(&c.children))).collect()
+    }
+
+    fn options(overlap: ChunkOverlap) -> ExtractOptions {
+        ExtractOptions { overlap, ..ExtractOptions::default() }
+    }
+
+    fn page_source(markdown: &str) -> PageSource {
+        PageSource {
+            markdown: markdown.to_string(),
+            file: "website/src/routes/docs/page/+page.svx".to_string(),
+            origin: SourceOrigin::Prerendered { root_path: String::new(), headings: Vec::new() },
+        }
+    }
+
+    fn page_info<'a>(source: &'a PageSource, blocks: &'a [Block]) -> PageInfo<'a> {
+        PageInfo {
+            item_id: "page".to_string(),
+            title: "Page".to_string(),
+            category: "guide".to_string(),
+            category_title: "Guide".to_string(),
+            keywords: Vec::new(),
+            summary: summarize(&source.markdown),
+            source,
+            blocks,
+        }
+    }
+
+    /// A top-level section of `paragraphs` short paragraphs.
+    fn doc_section(id: &str, category: &str, paragraphs: usize) -> DocSection {
+        let body = "Macros expand derives into plain TypeScript at build time. ".repeat(8);
+        let text = format!("# {}\n\n{}", id, vec![body; paragraphs].join("\n\n"));
+        DocSection {
+            id: id.to_string(),
+            title: id.to_string(),
+            category: category.to_lowercase(),
+            category_title: category.to_string(),
+            path: format!("{}.md", id),
+            use_cases: String::new(),
+            keywords: Vec::new(),
+            summary: format!("Summary of {}.", id),
+            tokens: count_tokens(&text),
+            is_chunked: None,
+            chunk_ids: None,
+            parent_id: None,
+            source_map: SourceMap {
+                file: format!("{}.svx", id),
+                lines: None,
+                node_path: None,
+                headings: Vec::new(),
+            },
+            text,
+        }
+    }
+
+    /// Chunks `markdown` as a page and returns each leaf chunk's emitted token count
+    /// alongside the chunk tree.
+    fn chunk_page(markdown: &str, options: &ExtractOptions) -> (Vec<Chunk>, Vec<usize>) {
+        fn leaf_tokens(
+            chunks: &[Chunk],
+            page: &PageInfo,
+            trail: &[String],
+            options: &ExtractOptions,
+            tokens: &mut Vec<usize>,
+        ) {
+            for chunk in chunks {
+                if chunk.children.is_empty() {
+                    tokens.push(count_tokens(&with_context(chunk, page, trail, options)));
+                }
+                let mut child_trail = trail.to_vec();
+                child_trail.push(chunk.heading.clone());
+                leaf_tokens(&chunk.children, page, &child_trail, options, tokens);
+            }
+        }
+
+        let source = page_source(markdown);
+        let blocks = parse_blocks(markdown);
+        let page = page_info(&source, &blocks);
+        let chunks = chunk_markdown(&page, options).unwrap();
+        let mut tokens = Vec::new();
+        leaf_tokens(&chunks, &page, &[], options, &mut tokens);
+        (chunks, tokens)
+    }
+
+    #[test]
+    fn split_before_indented_code_block_keeps_it_a_code_block() {
+        let code: String =
+            (0..60).map(|i| format!("    let value_{} = compute({});\n", i, i)).collect();
+        let markdown = format!(
+            "# Page\n\n## Body\n\n{}\n\n{}\n\n{}\n\n{}\n{}",
+            paragraph(40),
+            paragraph(40),
+            paragraph(40),
+            code,
+            paragraph(20)
+        );
+
+        let (chunks, _) = chunk_page(&markdown, &options(ChunkOverlap::None));
+        let starts_with_code = all_chunks(&chunks)
+            .into_iter()
+            .find(|c| c.children.is_empty() && c.content.starts_with("    let value_0"));
+        let chunk = starts_with_code.expect("a chunk starting at the indented code block");
+        assert_eq!(parse_blocks(&chunk.content)[0].kind, BlockKind::CodeBlock);
+    }
+
+    #[test]
+    fn unbalanced_chunk_reports_block_kinds() {
+        let markdown = "Intro paragraph.\n\n    indented code\n";
+        let blocks = parse_blocks(markdown);
+        let start = blocks[1].start;
+        let chunk = Chunk {
+            slug: "part-2".to_string(),
+            title: String::new(),
+            content: markdown[start..].trim().to_string(),
+            start,
+            end: markdown.len(),
+            heading: String::new(),
+            overlap: None,
+            children: Vec::new(),
+        };
+
+        let error = check_chunk_balanced(&chunk, &blocks).unwrap_err();
+        assert!(error.contains("should be a code block but re-parses as a paragraph"), "{}", error);
+    }
+
+    #[test]
+    fn chunks_never_split_fences() {
+        let fence = format!("```markdown\n## Not a heading\n\n{}\n```", paragraph(5));
+        let markdown = format!(
+            "# Page\n\n{}\n\n## First\n\n{}\n\n{}\n\n## Second\n\n{}",
+            paragraph(15),
+            paragraph(120),
+            fence,
+            paragraph(120)
+        );
+
+        let (chunks, _) = chunk_page(&markdown, &options(ChunkOverlap::None));
+        let slugs: Vec<&str> = chunks.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["overview", "first", "second"]);
+        assert!(chunks[1].content.ends_with("```"));
+    }
+
+    #[test]
+    fn single_section_page_over_budget_is_split() {
+        let body: Vec<String> = (0..6).map(|_| paragraph(40)).collect();
+        let single_h2 = format!("# Page\n\n## Body\n\n{}", body.join("\n\n"));
+        let no_h2 = format!("# Page\n\n{}", body.join("\n\n"));
+
+        for markdown in [single_h2, no_h2] {
+            assert!(should_chunk(&markdown));
+            let (chunks, _) = chunk_page(&markdown, &options(ChunkOverlap::None));
+            assert_eq!(chunks.len(), 1);
+            assert!(is_split(&chunks));
+            assert!(chunks[0].children.len() > 1);
+        }
+    }
+
+    #[test]
+    fn paragraph_overlap_counts_toward_the_budget() {
+        let body: Vec<String> = (0..8).map(|_| paragraph(40)).collect();
+        let markdown = format!("# Page\n\n## Body\n\n{}", body.join("\n\n"));
+
+        let (chunks, tokens) = chunk_page(&markdown, &options(ChunkOverlap::Paragraph));
+        assert!(chunks[0].children.len() > 1);
+        assert!(all_chunks(&chunks).iter().skip(2).all(|c| c.overlap.is_some()));
+        assert!(tokens.iter().all(|&t| t <= CHUNK_TOKEN_BUDGET), "{:?}", tokens);
+    }
+
+    #[test]
+    fn context_header_counts_toward_the_budget() {
+        let section = |name: &str| {
+            let body: Vec<String> = (0..5).map(|_| paragraph(37)).collect();
+            format!("## {}\n\n{}", name, body.join("\n\n"))
+        };
+        let markdown = format!(
+            "# Page\n\n{}\n\n{}\n\n{}",
+            paragraph(30),
+            section("Validation"),
+            section("Serialization")
+        );
+        let options = ExtractOptions { context_header: true, ..options(ChunkOverlap::Heading) };
+
+        let (chunks, tokens) = chunk_page(&markdown, &options);
+        assert!(chunks.iter().any(|c| !c.children.is_empty()));
+        assert!(tokens.iter().all(|&t| t <= CHUNK_TOKEN_BUDGET), "{:?}", tokens);
+    }
+
+    #[test]
+    fn summaries_of_sections_opening_with_code_use_their_prose() {
+        let lead_in = "## Parsing Input\n\nUse `parse_ts_macro_input!` to convert the token \
+                       stream:\n\n```rust\nlet input = parse_ts_macro_input!(input);\n```";
+        assert_eq!(
+            summarize(lead_in),
+            "Use `parse_ts_macro_input!` to convert the token stream."
+        );
+
+        let list = "## Example\n\n```ts\nclass User {}\n```\n\n## Required Imports\n\n\
+                    The generated code automatically imports:\n\n\
+                    - `DeserializeContext` from `macroforge/serde`\n";
+        assert_eq!(
+            summarize(list),
+            "The generated code automatically imports: `DeserializeContext` from \
+             `macroforge/serde`."
+        );
+
+        let code_only = "## DeriveInput Structure\n\nRust\n\n```\n// The parsed input\n\
+                         struct DeriveInput {\n    pub ident: Ident,\n}\n```";
+        assert_eq!(summarize(code_only), "DeriveInput Structure: `struct DeriveInput`");
+    }
+
+    #[test]
+    fn macro_options_documented_once_per_form_are_merged() {
+        let code_re = Regex::new(r"`([^`]+)`").unwrap();
+        let mut options = Vec::new();
+        for entry in ["`@default(42)` - Use 42", "`@default(\"hello\")` - Use \"hello\""] {
+            let option = macro_option(&code_re, entry, OptionLevel::Field, "default").unwrap();
+            merge_option(&mut options, option);
+        }
+
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].syntax, ["@default(42)", "@default(\"hello\")"]);
+        assert_eq!(options[0].description, "Use 42; Use \"hello\"");
+    }
+
+    #[test]
+    fn overview_members_defer_to_the_macro_page() {
+        let code_re = Regex::new(r"`([^`]+)`").unwrap();
+        let class = [MacroTarget::Class];
+        let page = generated_members(&code_re, "`static clone(value)`", &class, "clone");
+        let overview = generated_members(&code_re, "`clone():\u{a0}T`", &class, "overview");
+        assert_eq!(overview[0].signature, "clone(): T");
+
+        let catalog = MacroCatalog {
+            macros: vec![MacroInfo {
+                name: "Clone".to_string(),
+                section_id: "clone".to_string(),
+                description: String::new(),
+                decorator: None,
+                targets: class.to_vec(),
+                generated: page,
+                options: Vec::new(),
+                example_pairs: Vec::new(),
+            }],
+            overview: vec![("Clone".to_string(), overview[0].clone())],
+            ..MacroCatalog::default()
+        };
+        let macros = finish_macro_catalog(catalog, &[]);
+        let kinds: Vec<MemberKind> = macros[0].generated.iter().map(|m| m.kind).collect();
+        assert_eq!(kinds, [MemberKind::StaticMethod]);
+    }
+
+    #[test]
+    fn error_messages_come_only_from_error_sections() {
+        let markdown = "# Custom Macros\n\n## Example\n\n```rust\n\
+                        MacroforgeError::new(span, \"@derive(JSON) only works on classes\")\n\
+                        ```\n\n## Diagnostics\n\n```\n[macroforge] error at src/user.ts:5:1: \
+                        Unknown derive macro: InvalidMacro\n```\n";
+        let source = page_source(markdown);
+        let blocks = parse_blocks(markdown);
+        let examples = page_examples("custom", &source, &[]);
+
+        let errors = page_errors("custom", &source, &blocks, &[], &examples);
+        let patterns: Vec<Option<&str>> = errors.iter().map(|e| e.pattern.as_deref()).collect();
+        assert_eq!(patterns, [Some(r"^Unknown derive macro: \w+$")]);
+    }
+
+    #[test]
+    fn template_quick_reference_fits_one_chunk() {
+        let construct = |i: usize| TemplateConstruct {
+            id: format!("construct-{}", i),
+            name: format!("Construct {}", i),
+            syntax: vec![format!("{{#c{}}}", i)],
+            description: paragraph(2),
+            section_id: "ts-quote".to_string(),
+            example: Some(TemplateExample {
+                example_id: format!("ts-quote#example-{}", i),
+                code: (0..20).map(|n| format!("let value_{} = {};\n", n, n)).collect(),
+                output: None,
+            }),
+        };
+        let syntax = TemplateSyntax {
+            section_id: "ts-quote".to_string(),
+            title: "Template Syntax".to_string(),
+            summary: paragraph(2),
+            macros: Vec::new(),
+            forms: Vec::new(),
+            constructs: (0..12).map(construct).collect(),
+        };
+
+        let markdown = render_template_syntax(&syntax);
+        assert!(count_tokens(&markdown) <= CHUNK_TOKEN_BUDGET);
+        assert_eq!(markdown.matches("### Construct").count(), 12);
+        assert!(markdown.contains("let value_0"));
+    }
+
+    #[test]
+    fn function_overloads_become_one_api_entry() {
+        let code = "/** Expands macros. */\n\
+                    export declare function expand(code: string): string;\n\
+                    export declare function expand(code: string, file: string): string;\n\
+                    export { helper } from './helper';\n\
+                    export declare const VERSION: string;\n";
+        let mut entries = Vec::new();
+        assert!(declaration_entries(code, "index.d.ts", &mut entries));
+
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["expand", "VERSION"]);
+        assert_eq!(entries[0].signature, "function expand(code: string): string");
+        assert_eq!(entries[0].overloads, ["function expand(code: string, file: string): string"]);
+        assert_eq!(entries[0].doc, "Expands macros.");
+        assert_eq!(entries[0].source_map.lines, Some((2, 3)));
+    }
+
+    #[test]
+    fn declarations_published_twice_are_listed_once() {
+        let code = "export declare function expand(code: string): string;\n\
+                    export declare function expand(code: string, file: string): string;\n\
+                    export interface NativePlugin { name: string; }\n";
+        let mut entries = Vec::new();
+        assert!(declaration_entries(code, "dist/index.d.ts", &mut entries));
+        let mts = code.replace("(code: string)", "(code: string | Buffer)");
+        assert!(declaration_entries(&mts, "dist/index.d.mts", &mut entries));
+
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["expand", "NativePlugin"]);
+        assert_eq!(
+            entries[0].overloads,
+            [
+                "function expand(code: string, file: string): string",
+                "function expand(code: string | Buffer): string",
+            ]
+        );
+        assert_eq!(entries[0].source_map.file, "dist/index.d.ts");
+        assert_eq!(entries[0].source_map.lines, Some((1, 2)));
+    }
+
+    #[test]
+    fn capped_book_abridges_only_pages_that_do_not_fit() {
+        let sections = vec![
+            doc_section("intro", "Guide", 1),
+            doc_section("reference", "Guide", 40),
+            doc_section("derives", "Macros", 1),
+        ];
+        let book = render_book(&sections, &HashMap::new(), Some(600));
+
+        assert!(count_tokens(&book) <= 600);
+        assert!(book.contains("_Abridged; the full page is `reference.md`._"));
+        assert!(!book.contains("_Abridged; the full page is `intro.md`._"));
+        assert!(!book.contains("_Abridged; the full page is `derives.md`._"));
+        assert!(book.contains("## derives"));
+        assert!(!book.contains("more pages were left out"));
+    }
+
+    #[test]
+    fn large_cap_keeps_the_whole_book() {
+        let sections = vec![
+            doc_section("intro", "Guide", 2),
+            doc_section("derives", "Macros", 3),
+        ];
+        let full = render_book(&sections, &HashMap::new(), None);
+        let capped = render_book(&sections, &HashMap::new(), Some(100_000));
+
+        assert_eq!(capped, full);
+        assert!(count_tokens(&capped) <= 100_000);
+    }
+
+    #[test]
+    fn repeated_headings_get_unique_chunk_slugs() {
+        let markdown = format!(
+            "# Page\n\n{}\n\n## Example\n\n{}\n\n## Example\n\n{}\n\n## Example\n\n{}",
+            paragraph(15),
+            paragraph(60),
+            paragraph(60),
+            paragraph(60)
+        );
+
+        let (chunks, _) = chunk_page(&markdown, &options(ChunkOverlap::None));
+        let slugs: Vec<&str> = chunks.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(slugs, ["overview", "example", "example-2", "example-3"]);
+    }
+
+    #[test]
+    fn pages_sharing_a_leaf_name_get_qualified_ids() {
+        let item = |href: &str| NavItem { title: href.to_string(), href: href.to_string() };
+        let navigation = vec![NavSection {
+            title: "Docs".to_string(),
+            items: vec![
+                item("/docs/integration/svelte"),
+                item("/docs/examples/svelte"),
+                item("/docs/getting-started/installation"),
+            ],
+        }];
+
+        let ids = assign_page_ids(&navigation, &get_category_id_map());
+        assert_eq!(ids["/docs/integration/svelte"], "integration-svelte");
+        assert_eq!(ids["/docs/examples/svelte"], "examples-svelte");
+        assert_eq!(ids["/docs/getting-started/installation"], "installation");
+    }
+
+    #[test]
+    fn duplicate_section_ids_and_paths_are_rejected() {
+        let mut clash = doc_section("intro", "Guide", 1);
+        clash.title = "Other intro".to_string();
+        let sections = vec![doc_section("intro", "Guide", 1), clash];
+
+        let errors = validate_unique(&sections).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("duplicate section id 'intro'"));
+        assert!(errors[1].starts_with("duplicate output path 'intro.md'"));
+        assert!(validate_unique(&sections[..1]).is_ok());
+    }
+
+    #[test]
+    fn lossless_check_reports_dropped_text() {
+        let markdown = format!(
+            "# Page\n\n{}\n\n## First\n\n{}\n\nA closing remark.\n\n## Second\n\n{}",
+            paragraph(15),
+            paragraph(120),
+            paragraph(120)
+        );
+        let options = options(ChunkOverlap::Paragraph);
+        let source = page_source(&markdown);
+        let blocks = parse_blocks(&markdown);
+        let page = page_info(&source, &blocks);
+        let mut chunks = chunk_markdown(&page, &options).unwrap();
+        assert_eq!(verify_lossless(&markdown, &chunks, &page, &options), Ok(()));
+
+        chunks[1].content = chunks[1].content.replace("\n\nA closing remark.", "");
+        let error = verify_lossless(&markdown, &chunks, &page, &options).unwrap_err();
+        assert_eq!(error, "  dropped: A closing remark.");
+    }
+
+    #[test]
+    fn source_lines_account_for_stripped_boilerplate() {
+        let svx = "<!-- generated -->\n\
+                   <svelte:head>\n  <title>Page</title>\n</svelte:head>\n\n\
+                   # Page\n\nIntro.\n\n## Second\n\nBody text.\n";
+        let (markdown, offsets) = strip_mdsvex_boilerplate(svx);
+        assert!(markdown.starts_with("# Page"));
+        let line_starts = std::iter::once(0)
+            .chain(svx.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let source = PageSource {
+            markdown: markdown.clone(),
+            file: "page.svx".to_string(),
+            origin: SourceOrigin::Mdsvex { offsets, line_starts },
+        };
+        let blocks = parse_blocks(&markdown);
+
+        let start = markdown.find("## Second").unwrap();
+        let map = source_map(&source, &blocks, start, markdown.len());
+        assert_eq!(map.lines, Some((10, 12)));
+        assert_eq!(map.headings.len(), 1);
+        assert_eq!(map.headings[0].line, Some(10));
+    }
+
+    #[test]
+    fn identifiers_split_into_words() {
+        assert_eq!(identifier_parts("fromJSON"), ["from", "json"]);
+        assert_eq!(identifier_parts("HTMLParser"), ["html", "parser"]);
+        assert_eq!(identifier_parts("ts_macro_derive"), ["ts", "macro", "derive"]);
+        assert_eq!(identifier_parts("@serde"), ["serde"]);
+    }
+
+    #[test]
+    fn configured_keywords_drop_stemmed_duplicates() {
+        let markdown = "---\nkeywords: [macro, validation]\n---\n\n# Page\n";
+        let keywords = configured_keywords("macros, caching", markdown, true);
+
+        let terms: Vec<(&str, KeywordSource)> =
+            keywords.iter().map(|k| (k.term.as_str(), k.source)).collect();
+        assert_eq!(
+            terms,
+            [
+                ("macros", KeywordSource::Manual),
+                ("caching", KeywordSource::Manual),
+                ("validation", KeywordSource::Frontmatter),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracted_keywords_favor_distinctive_terms() {
+        let shared = "Macros expand at build time.";
+        let mut sections: Vec<DocSection> =
+            ["intro", "serde", "cli"].iter().map(|id| doc_section(id, "Guide", 0)).collect();
+        for section in &mut sections {
+            section.text = format!("# {}\n\n{}\n", section.title, shared);
+        }
+        sections[1].text.push_str(
+            "\nUse `fromJSON` for deserialization. Deserialization validates fields.\n",
+        );
+        sections[1].keywords =
+            vec![Keyword { term: "json".to_string(), source: KeywordSource::Manual }];
+        assign_keywords(&mut sections, true);
+
+        let terms: Vec<&str> = sections[1].keywords.iter().map(|k| k.term.as_str()).collect();
+        assert_eq!(terms[0], "json");
+        assert!(terms.contains(&"fromJSON"), "{:?}", terms);
+        assert!(terms.contains(&"deserialization"), "{:?}", terms);
+        assert!(!terms[1..].contains(&"json"));
+        assert!(!terms.contains(&"macros"), "{:?}", terms);
+        assert_eq!(sections[1].use_cases, terms.join(", "));
+    }
+
+    #[test]
+    fn index_terms_include_identifier_parts() {
+        // tests/docs-loader.test.js pins the server's tokenizer to the same terms
+        assert_eq!(
+            index_terms("Use serializeWithContext for HTMLParser caching"),
+            ["serializewithcontext", "serializ", "context", "htmlparser", "html", "parser", "cach"]
+        );
+    }
+
+    #[test]
+    fn search_postings_hold_per_field_frequencies() {
+        let mut sections =
+            vec![doc_section("validation", "Guide", 0), doc_section("intro", "Guide", 0)];
+        sections[0].text = "## Validators\n\nValidators check fields.\n".to_string();
+        sections[1].keywords =
+            vec![Keyword { term: "validators".to_string(), source: KeywordSource::Manual }];
+        let index = build_search_index(&sections);
+
+        assert_eq!(index.docs, ["validation", "intro"]);
+        assert_eq!(index.postings["validator"], [0, 0, 1, 0, 1, 1, 0, 0, 1, 0]);
+        assert_eq!(index.postings["validation"], [0, 1, 0, 0, 0]);
+        assert_eq!(index.lengths[0], [1, 1, 0, 3]);
+        assert_eq!(index.avg_lengths, [1.0, 1.0, 0.5, 1.5]);
+    }
+
+    #[test]
+    fn feature_buckets_are_fnv1a() {
+        // tests/docs-loader.test.js expects the server to embed into the same buckets
+        assert_eq!(feature_bucket("a"), 300);
+        assert_eq!(feature_bucket("serializ"), 126);
+        assert_eq!(feature_bucket("~seria"), 891);
+        assert_eq!(vector_features("serialize macro"), ["~seria", "serializ", "macro"]);
+    }
+
+    #[test]
+    fn section_vectors_are_unit_length() {
+        let sections = vec![doc_section("intro", "Guide", 1), doc_section("derives", "Macros", 2)];
+        let vectors = build_section_vectors(&sections);
+
+        assert_eq!(vectors.docs, ["intro", "derives"]);
+        assert_eq!(vectors.idf.len(), VECTOR_DIMENSIONS);
+        for vector in &vectors.vectors {
+            assert!(vector.buckets.windows(2).all(|w| w[0] < w[1]));
+            let norm: f64 = vector.weights.iter().map(|w| w * w).sum();
+            assert!((norm - 1.0).abs() < 1e-3, "{}", norm);
+        }
+    }
+
+    #[test]
+    fn alias_spellings_cover_case_styles() {
+        assert_eq!(
+            alias_spellings("PartialEq"),
+            ["partialeq", "partial-eq", "partial_eq", "partial eq"]
+        );
+        assert_eq!(alias_spellings("@serde"), ["@serde", "serde"]);
+        assert_eq!(alias_spellings("if"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn alias_dictionary_maps_names_to_sections() {
+        let item = |title: &str, href: &str| NavItem {
+            title: title.to_string(),
+            href: href.to_string(),
+        };
+        let navigation = vec![NavSection {
+            title: "Built-in Macros".to_string(),
+            items: vec![item("PartialEq", "/docs/builtin-macros/partial-eq")],
+        }];
+        let page_ids = assign_page_ids(&navigation, &get_category_id_map());
+        let mut page = doc_section("partial-eq", "Built-in Macros", 0);
+        page.text = "# PartialEq\n\n## Comparing with `isEqual`\n".to_string();
+        let mut chunks = vec![doc_section("a/examples", "X", 0), doc_section("b/examples", "X", 0)];
+        for chunk in &mut chunks {
+            chunk.title = "Page: Examples".to_string();
+            chunk.parent_id = Some("page".to_string());
+        }
+        let mut sections = vec![page];
+        sections.extend(chunks);
+
+        let dictionary = build_alias_dictionary(&navigation, &page_ids, &sections);
+        let ids = |alias: &str| dictionary.aliases.get(alias).map(|e| e.ids.clone());
+        for alias in ["partialeq", "partial_eq", "partial eq", "@derive(partialeq)", "is-equal"] {
+            assert_eq!(ids(alias), Some(vec!["partial-eq".to_string()]), "{}", alias);
+        }
+        assert_eq!(dictionary.aliases["is-equal"].sources, [AliasSource::Identifier]);
+        assert_eq!(ids("examples"), None);
+    }
+
+    #[test]
+    fn code_examples_are_classified_from_their_captions_and_prose() {
+        let markdown = "# Page\n\n## Setup\n\nInstall the package first.\n\nnpm\n\n\
+                        ```\nnpm install macroforge\n```\n\n## Usage\n\n\
+                        Add the decorator to a class.\n\n\
+                        ```typescript\n/** @derive(Debug) */\nclass User {}\n```\n\n\
+                        This generates the following code.\n\n\
+                        ```typescript\nclass User { toString() {} }\n```\n\n\
+                        vite.config.ts\n\n```\nexport default {}\n```\n";
+        let examples = page_examples("page", &page_source(markdown), &[]);
+
+        let summary: Vec<(&str, &str, ExampleKind, Option<&str>)> = examples
+            .iter()
+            .map(|e| (e.id.as_str(), e.language.as_str(), e.kind, e.file_name.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("page#example-1", "bash", ExampleKind::Shell, None),
+                ("page#example-2", "typescript", ExampleKind::Input, None),
+                ("page#example-3", "typescript", ExampleKind::Expanded, None),
+                ("page#example-4", "typescript", ExampleKind::Config, Some("vite.config.ts")),
+            ]
+        );
+        assert_eq!(examples[0].heading.as_deref(), Some("Setup"));
+        assert_eq!(examples[0].context.as_deref(), Some("Install the package first."));
+        assert_eq!(examples[0].code, "npm install macroforge");
+    }
+
+    #[test]
+    fn example_pairs_link_inputs_to_their_expansion() {
+        let markdown = "# Page\n\n## Debug\n\n\
+                        ```typescript before\n/** @derive(Debug, Clone) */\n\
+                        interface Point { x: number; }\n```\n\n\
+                        ```typescript after\ninterface Point { x: number; }\n\
+                        export namespace Point {\n\
+                        export function toString(p: Point): string { return ''; }\n}\n```\n\n\
+                        ## Broken\n\n```typescript before\nclass {\n```\n\n\
+                        ```bash\nnpm run build\n```\n\n```typescript after\nclass {\n```\n";
+        let mut examples = page_examples("page", &page_source(markdown), &[]);
+        let pairs = pair_examples(&mut examples);
+
+        assert_eq!(pairs.len(), 1);
+        let pair = &pairs[0];
+        assert_eq!(pair.input, "page#example-1");
+        assert_eq!(pair.output, "page#example-2");
+        assert_eq!(pair.macros, ["Debug", "Clone"]);
+        assert_eq!(pair.types, ["Point"]);
+        assert_eq!(pair.generated, ["Point.toString"]);
+        assert!(pair.verified);
+        assert_eq!(examples[1].pair_id.as_deref(), Some("page#pair-1"));
+        assert_eq!(examples[2].pair_id, None);
+    }
+
+    #[test]
+    fn serde_options_merge_across_macros() {
+        let code_re = Regex::new(r"`([^`]+)`").unwrap();
+        let serde_macro = |name: &str, entries: &[&str]| MacroInfo {
+            name: name.to_string(),
+            section_id: name.to_lowercase(),
+            description: String::new(),
+            decorator: Some("@serde".to_string()),
+            targets: vec![MacroTarget::Class],
+            generated: Vec::new(),
+            options: entries
+                .iter()
+                .filter_map(|e| macro_option(&code_re, e, OptionLevel::Field, &name.to_lowercase()))
+                .collect(),
+            example_pairs: Vec::new(),
+        };
+        let macros = [
+            serde_macro("Serialize", &["`skip` / `skipSerializing` - Omit the field"]),
+            serde_macro("Deserialize", &["`default` - Use the type default", "`skip` - Skip it"]),
+            serde_macro("Deserialize", &["`default = expr` - Use `expr` when missing"]),
+        ];
+        let validators = validator_entries(
+            &code_re,
+            "`minLength(n)`, `maxLength(n)` - Length bounds",
+            "String",
+            "deserialize/validation",
+        );
+
+        let reference = build_serde_reference(&macros, validators);
+        let names: Vec<&str> = reference.options.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["skip", "default"]);
+        assert_eq!(reference.options[0].aliases, ["skipSerializing"]);
+        assert_eq!(reference.options[0].usages.len(), 2);
+        let default = &reference.options[1].params;
+        assert_eq!((default[0].name.as_str(), default[0].kind.as_str()), ("expr", "expression"));
+        assert!(default[0].optional);
+
+        let validators: Vec<(&str, &str)> = reference
+            .validators
+            .iter()
+            .map(|v| (v.name.as_str(), v.params[0].kind.as_str()))
+            .collect();
+        assert_eq!(validators, [("minLength", "number"), ("maxLength", "number")]);
+        assert_eq!(reference.validators[0].field_types, ["string"]);
+    }
+
+    #[test]
+    fn support_matrix_combines_macro_pages_and_support_sections() {
+        let info = |name: &str| MacroInfo {
+            name: name.to_string(),
+            section_id: name.to_lowercase(),
+            description: String::new(),
+            decorator: None,
+            targets: vec![MacroTarget::Class],
+            generated: Vec::new(),
+            options: Vec::new(),
+            example_pairs: Vec::new(),
+        };
+        let support = |target: MacroTarget, heading: &str, note: &str| SupportSection {
+            target,
+            heading: heading.to_string(),
+            section_id: "overview".to_string(),
+            note: note.to_string(),
+        };
+        let catalog = MacroCatalog {
+            macros: vec![info("Debug"), info("Clone")],
+            support: vec![
+                support(MacroTarget::Enum, "Enum Support", "All built-in macros work with enums:"),
+                support(MacroTarget::Interface, "Interface Support", "Interfaces get functions:"),
+            ],
+            ..MacroCatalog::default()
+        };
+        let markdown = "# Overview\n\n## Interface Support\n\nInterfaces get functions:\n\n\
+                        ```typescript\n/** @derive(Debug) */\ninterface Point { x: number }\n```\n";
+        let examples = page_examples("overview", &page_source(markdown), &[]);
+
+        let matrix = build_support_matrix(&catalog, &examples);
+        let supported = |entry: &MacroSupport| -> Vec<bool> {
+            entry.targets.iter().map(|t| t.supported).collect()
+        };
+        assert_eq!(supported(&matrix[0]), [true, true, true, false]);
+        assert_eq!(supported(&matrix[1]), [true, false, true, false]);
+        assert_eq!(matrix[0].targets[1].examples, ["overview#example-1"]);
+        assert_eq!(matrix[1].targets[2].notes[0].text, "All built-in macros work with enums.");
+
+        let page = render_support_matrix(&matrix, &catalog.support);
+        assert!(page.contains("| `Clone` | Yes | No | Yes | No |"));
+    }
+
+    #[test]
+    fn cli_commands_read_usage_arguments_flags_and_examples() {
+        let markdown = "# CLI\n\n### macroforge expand\n\nExpand macros in a file.\n\n\
+                        ```bash\nmacroforge expand <input> [options]\n```\n\n\
+                        #### Arguments\n\n| Argument | Description |\n|---|---|\n\
+                        | `<input>` | File to expand |\n\n\
+                        #### Options\n\n| Option | Description |\n|---|---|\n\
+                        | `-o, --out <path>` | Output file (defaults to `stdout`) |\n\
+                        | `--scan` | Scan a directory |\n\n\
+                        ## Examples\n\nExpand one file:\n\n\
+                        ```bash\nmacroforge expand src/user.ts\nnpm test\n```\n";
+        let source = page_source(markdown);
+        let blocks = parse_blocks(markdown);
+        let examples = page_examples("cli", &source, &[]);
+
+        let commands = page_cli_commands("cli", &source, &blocks, &[], &examples);
+        assert_eq!(commands.len(), 1);
+        let expand = &commands[0];
+        assert_eq!(expand.name, "macroforge expand");
+        assert_eq!(expand.usage, "macroforge expand <input> [options]");
+        assert_eq!(expand.description, "Expand macros in a file.");
+        assert_eq!(expand.arguments.len(), 1);
+        assert_eq!(expand.arguments[0].name, "input");
+        assert!(expand.arguments[0].required);
+
+        let flags: Vec<_> = expand
+            .flags
+            .iter()
+            .map(|f| {
+                (f.short.as_deref(), f.long.as_deref(), f.value.as_deref(), f.default.as_deref())
+            })
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                (Some("-o"), Some("--out"), Some("path"), Some("stdout")),
+                (None, Some("--scan"), None, None),
+            ]
+        );
+
+        assert_eq!(expand.examples.len(), 1);
+        assert_eq!(expand.examples[0].command, "macroforge expand src/user.ts");
+        assert_eq!(expand.examples[0].description.as_deref(), Some("Expand one file"));
+        assert_eq!(expand.examples[0].example_id, "cli#example-2");
+    }
+
+    #[test]
+    fn jsonc_keeps_comments_and_urls() {
+        let code = "{\n  // Load native macros\n  // from node_modules\n\
+                    \"allowNativeMacros\": true,\n\
+                    \"homepage\": \"https://example.com\", // trailing\n}";
+        let (value, comments) = parse_jsonc(code).unwrap();
+
+        assert_eq!(value["homepage"], "https://example.com");
+        assert_eq!(comments["allowNativeMacros"], "Load native macros from node_modules");
+        assert_eq!(comments.len(), 1);
+    }
+
+    #[test]
+    fn config_schema_merges_documented_options_and_examples() {
+        let markdown = "# Configuration\n\nmacroforge.json\n\n```json\n{\n\
+                        \"allowNativeMacros\": true,\n\
+                        \"limits\": { \"maxDiagnostics\": 50 },\n\
+                        \"homepage\": \"https://example.com\"\n}\n```\n\n\
+                        ### allowNativeMacros\n\nAllow native macros.\n\n\
+                        **Type:** `boolean` · **Default:** `false`\n\n\
+                        ### limits.maxDiagnostics\n\n**Type:** `number`\n\n\
+                        ### mode\n\n**Type:** `\"strict\" | \"loose\"`\n";
+        let source = page_source(markdown);
+        let blocks = parse_blocks(markdown);
+        let examples = page_examples("configuration", &source, &[]);
+        assert!(is_config_page(&examples));
+
+        let options = page_config_options("configuration", &source, &blocks, &[]);
+        let examples: Vec<&CodeExample> = examples.iter().collect();
+        let (schema, report) = build_config_schema("Config.", &options, &examples);
+
+        let properties = &schema["properties"];
+        assert_eq!(
+            properties["allowNativeMacros"],
+            serde_json::json!({
+                "type": "boolean",
+                "default": false,
+                "description": "Allow native macros.",
+                "x-section-id": "configuration",
+                "examples": [true],
+            })
+        );
+        assert_eq!(properties["limits"]["properties"]["maxDiagnostics"]["type"], "number");
+        assert_eq!(properties["limits"]["additionalProperties"], false);
+        assert_eq!(properties["mode"]["enum"], serde_json::json!(["strict", "loose"]));
+        assert_eq!(properties["homepage"]["type"], "string");
+
+        let keys = |entries: &[ConfigReportEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.key.clone()).collect()
+        };
+        assert_eq!(keys(&report.undocumented), ["homepage"]);
+        assert_eq!(keys(&report.unused), ["mode"]);
+    }
+
+    #[test]
+    fn rustdoc_items_render_signatures_members_and_impls() {
+        use serde_json::{json, Value};
+
+        let path = |name: &str, args: Vec<Value>| {
+            let args: Vec<Value> = args.into_iter().map(|ty| json!({ "type": ty })).collect();
+            let args = json!({ "angle_bracketed": { "args": args, "constraints": [] } });
+            json!({ "resolved_path": { "path": name, "args": args } })
+        };
+        let function = |inputs: Value, output: Value| {
+            json!({ "function": {
+                "sig": { "inputs": inputs, "output": output },
+                "generics": { "params": [] },
+                "header": { "is_const": false, "is_async": false, "is_unsafe": false },
+            } })
+        };
+        let str_ref = json!({ "borrowed_ref": {
+            "lifetime": "'a",
+            "is_mutable": false,
+            "type": { "primitive": "str" },
+        } });
+        let options = path("Option", vec![path("Options", vec![])]);
+        let result = path("Result", vec![path("String", vec![]), path("MacroError", vec![])]);
+        let mut expand = function(json!([["code", str_ref], ["options", options]]), result);
+        expand["function"]["generics"]["params"] = json!([{ "name": "'a", "kind": {} }]);
+        let indent = function(json!([["depth", { "primitive": "usize" }]]), path("String", vec![]));
+        let new = function(json!([]), json!({ "generic": "Self" }));
+        let internal = function(json!([]), Value::Null);
+
+        let doc = json!({
+            "format_version": 39,
+            "root": 0,
+            "crate_version": "0.1.0",
+            "index": {
+                "0": { "name": "macroforge_ts", "docs": "Macro expansion.",
+                       "inner": { "module": { "items": [1, 2, 5, 7] } } },
+                "1": { "name": "expand", "docs": "Expands macros.", "visibility": "public",
+                       "span": { "filename": "src/lib.rs", "begin": [10, 0], "end": [12, 1] },
+                       "inner": expand },
+                "2": { "name": "Options", "visibility": "public", "inner": { "struct": {
+                    "generics": { "params": [] },
+                    "kind": { "plain": { "fields": [3] } },
+                    "impls": [4, 8],
+                } } },
+                "3": { "name": "strict", "docs": "Reject unknown macros.", "visibility": "public",
+                       "inner": { "struct_field": { "primitive": "bool" } } },
+                "4": { "inner": { "impl": { "trait": null, "blanket_impl": null, "items": [6] } } },
+                "5": { "name": "internal", "visibility": "crate", "inner": internal },
+                "6": { "name": "new", "visibility": "public", "inner": new },
+                "7": { "name": "util", "visibility": "public",
+                       "inner": { "module": { "items": [9] } } },
+                "8": { "inner": { "impl": { "trait": { "path": "Clone" } } } },
+                "9": { "name": "indent", "visibility": "public", "inner": indent },
+            },
+        });
+
+        let krate = rustdoc_crate(&doc).unwrap();
+        assert_eq!(krate.name, "macroforge_ts");
+        assert_eq!(krate.version.as_deref(), Some("0.1.0"));
+        let items: Vec<(&str, &str)> =
+            krate.items.iter().map(|i| (i.path.as_str(), i.signature.as_str())).collect();
+        assert_eq!(
+            items,
+            [
+                (
+                    "expand",
+                    "pub fn expand<'a>(code: &'a str, options: Option<Options>) \
+                     -> Result<String, MacroError>"
+                ),
+                ("Options", "pub struct Options"),
+                ("util::indent", "pub fn indent(depth: usize) -> String"),
+            ]
+        );
+        assert_eq!(krate.items[0].source_map.lines, Some((10, 12)));
+        let members: Vec<&str> = krate.items[1].members.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(members, ["pub strict: bool", "pub fn new() -> Self"]);
+        assert_eq!(krate.items[1].members[0].1, "Reject unknown macros.");
+        assert_eq!(krate.items[1].implements, ["Clone"]);
+        assert_eq!(rust_slug("util::indent"), "util-indent");
+    }
+
+    #[test]
+    fn rustdoc_markdown_demotes_headings_and_hides_doctest_lines() {
+        let docs = "See [`expand`].\n\n# Examples\n\n```\n# use macroforge_ts::expand;\n\
+                    expand(\"\");\n```\n\n```text\n# kept\n```";
+        assert_eq!(
+            rustdoc_markdown(docs, 2),
+            "See `expand`.\n\n### Examples\n\n```rust\nexpand(\"\");\n```\n\n```text\n# kept\n```"
+        );
+    }
+
+    #[test]
+    fn llms_txt_lists_top_level_pages_by_category() {
+        let mut sections = vec![
+            doc_section("intro", "Guide", 1),
+            doc_section("derives", "Macros", 1),
+            doc_section("install", "Guide", 1),
+            doc_section("intro/setup", "Guide", 1),
+        ];
+        sections[0].summary = "Start here. Then read the macros.".to_string();
+        sections[3].parent_id = Some("intro".to_string());
+
+        let llms = render_llms_txt(&sections);
+        let body = llms.split_once("\n## ").unwrap().1;
+        assert_eq!(
+            body,
+            "Guide\n\n- [intro](intro.md): Start here.\n\
+             - [install](install.md): Summary of install.\n\n\
+             ## Macros\n\n- [derives](derives.md): Summary of derives.\n"
+        );
+
+        let full = render_llms_full_txt(&sections);
+        assert_eq!(full.matches("\nSource: ").count(), 3);
+        assert!(full.contains("Source: install.md\n\n# install\n"));
+    }
+}
@@ -14,6 +14,8 @@
  * - `macroforge-autofixer` - Validate TypeScript code with @derive decorators
  * - `expand-code` - Expand Macroforge macros and show generated code
  * - `get-macro-info` - Get documentation for macros and decorators
+ * - `find-examples` - Find code examples from the documentation
+ * - `validate-config` - Check a macroforge.json against the documented options
  *
  * @example
  * ```bash
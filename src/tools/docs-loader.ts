@@ -10,6 +10,10 @@
  * Key features:
  * - Loads documentation sections from disk with lazy content loading
  * - Supports multiple search strategies (exact, partial, fuzzy, category)
+ * - Ranks fuzzy searches with the BM25 index precomputed by the extractor
+ * - Finds semantically similar sections with precomputed hashed TF-IDF vectors
+ * - Resolves synonyms and alternate spellings through the generated alias dictionary
+ * - Searches the catalog of code examples extracted from the docs
  * - Handles chunked documentation for large sections
  * - Provides type-safe interfaces for documentation structure
  *
@@ -33,8 +37,44 @@ const __filename = fileURLToPath(import.meta.url);
 /** Current directory path for ESM module resolution */
 const __dirname = dirname(__filename);
 
-/** Path to the docs directory containing sections.json and markdown files */
-const docsDir = join(__dirname, '..', '..', 'docs');
+/**
+ * Path to the docs directory containing sections.json and markdown files, which
+ * `MACROFORGE_DOCS_DIR` overrides (e.g. to point tests at fixture data)
+ */
+const docsDir = process.env.MACROFORGE_DOCS_DIR ?? join(__dirname, '..', '..', 'docs');
+
+/** Cached search index: undefined until loaded, null if the file is missing */
+let cachedSearchIndex: SearchIndex | null | undefined;
+
+/** Cached alias dictionary: undefined until loaded, null if the file is missing */
+let cachedAliases: AliasDictionary | null | undefined;
+
+/** Cached code examples: undefined until loaded, empty if the file is missing */
+let cachedExamples: CodeExample[] | undefined;
+
+/** Cached section vectors: undefined until loaded, null if the file is missing */
+let cachedSectionVectors: SectionVectors | null | undefined;
+
+/** Cached macro catalog: undefined until loaded, empty if the file is missing */
+let cachedMacros: MacroInfo[] | undefined;
+
+/** Cached `@serde` reference: undefined until loaded, null if the file is missing */
+let cachedSerdeReference: SerdeReference | null | undefined;
+
+/** Cached `macroforge.json` schema: undefined until loaded, null if the file is missing */
+let cachedConfigSchema: JsonSchema | null | undefined;
+
+/** Cached error catalog: undefined until loaded, empty if the file is missing */
+let cachedErrors: ErrorEntry[] | undefined;
+
+/** Compiled patterns of the error catalog, most specific first */
+let cachedErrorPatterns: Array<{ entry: ErrorEntry; regex: RegExp }> | undefined;
+
+/** Minimum cosine similarity for a section to count as similar to a query */
+const MIN_SIMILARITY = 0.1;
+
+/** Rank offset used when fusing keyword and vector rankings (reciprocal rank fusion) */
+const RANK_FUSION_K = 60;
 
 /**
  * Represents a documentation section with metadata and optional content.
@@ -49,10 +89,14 @@ const docsDir = join(__dirname, '..', '..', 'docs');
  * @property category_title - Human-readable category name
  * @property path - Relative path to the markdown content file from docs directory
  * @property use_cases - Comma-separated keywords describing when this doc is useful
+ * @property keywords - The same keywords with where each came from
+ * @property summary - Short extractive summary of the section content
+ * @property tokens - LLM token count of the content (whole page for chunked parents)
  * @property content - The actual markdown content (loaded lazily, undefined for chunked parents)
  * @property is_chunked - True if this section is split into multiple sub-chunks
  * @property parent_id - For sub-chunks, the ID of the parent section
  * @property chunk_ids - For chunked parents, ordered list of child chunk IDs
+ * @property source_map - Where the section lives in the website sources
  */
 export interface Section {
     id: string;
@@ -61,10 +105,372 @@ export interface Section {
     category_title: string;
     path: string;
     use_cases: string;
+    keywords?: Keyword[];
+    summary?: string;
+    tokens?: number;
     content?: string;
     is_chunked?: boolean;
     parent_id?: string;
     chunk_ids?: string[];
+    source_map?: SourceMap;
+}
+
+/**
+ * A section keyword and its origin: configured by hand in the extractor, read from
+ * page frontmatter, or extracted with TF-IDF over the documentation corpus.
+ */
+export interface Keyword {
+    term: string;
+    source: 'manual' | 'frontmatter' | 'extracted';
+}
+
+/**
+ * Location of a section in the website sources, so docs can be cited as file:line.
+ *
+ * @property file - Source file relative to the repository root
+ * @property lines - First and last source line (1-based, inclusive) for mdsvex pages
+ * @property node_path - HTML node path for pages extracted from prerendered HTML
+ * @property headings - Each heading in the section with its line or node path
+ */
+export interface SourceMap {
+    file: string;
+    lines?: [number, number];
+    node_path?: string;
+    headings: Array<{
+        level: number;
+        text: string;
+        line?: number;
+        node_path?: string;
+    }>;
+}
+
+/**
+ * Precomputed BM25F inverted index written by `scripts/extract-docs.rs` to
+ * `docs/search-index.json`.
+ *
+ * Postings are flat arrays of `fields.length + 1` numbers per document: the
+ * document number (an index into `docs`) followed by the term frequency in each
+ * field, in `fields` order.
+ *
+ * @property version - Format version (currently 1)
+ * @property fields - Indexed fields in posting order (title, headings, keywords, body)
+ * @property weights - BM25F weight of each field
+ * @property k1 - BM25 term frequency saturation
+ * @property b - BM25 length normalization
+ * @property stop_words - Words dropped by the tokenizer
+ * @property docs - Section IDs by document number
+ * @property lengths - Number of terms in each field, by document number
+ * @property avg_lengths - Mean number of terms in each field
+ * @property postings - Stemmed term to flattened postings
+ */
+export interface SearchIndex {
+    version: number;
+    fields: string[];
+    weights: number[];
+    k1: number;
+    b: number;
+    stop_words: string[];
+    docs: string[];
+    lengths: number[][];
+    avg_lengths: number[];
+    postings: Record<string, number[]>;
+}
+
+/**
+ * Hashed TF-IDF vectors for every section, written by `scripts/extract-docs.rs` to
+ * `docs/vectors.json`.
+ *
+ * A feature is an index term (see {@link tokenize}) or, for terms longer than
+ * `prefix_length`, `~` followed by the term's first `prefix_length` characters. Its
+ * bucket is the 32-bit FNV-1a hash of its UTF-8 bytes modulo `dimensions`.
+ *
+ * @property version - Format version (currently 1)
+ * @property dimensions - Number of hashed buckets
+ * @property prefix_length - Length of prefix features
+ * @property idf - Inverse document frequency of each bucket
+ * @property docs - Section IDs, parallel to `vectors`
+ * @property vectors - Unit-length sparse vectors as ascending buckets and weights
+ */
+export interface SectionVectors {
+    version: number;
+    dimensions: number;
+    prefix_length: number;
+    idf: number[];
+    docs: string[];
+    vectors: Array<{ buckets: number[]; weights: number[] }>;
+}
+
+/**
+ * Alias dictionary written by `scripts/extract-docs.rs` to `docs/aliases.json`.
+ *
+ * Keys are lowercase spellings (`partial-eq`, `partialeq`, `@derive(partialeq)`,
+ * `equals`, old section IDs); each maps to section IDs, most specific first.
+ *
+ * @property version - Format version (currently 1)
+ * @property aliases - Spelling to target section IDs and where the alias came from
+ */
+export interface AliasDictionary {
+    version: number;
+    aliases: Record<string, AliasEntry>;
+}
+
+/**
+ * Target section IDs of an alias and where the alias came from.
+ */
+export interface AliasEntry {
+    ids: string[];
+    sources: Array<'title' | 'href' | 'identifier' | 'synonym' | 'renamed'>;
+}
+
+/**
+ * A fenced code block from the docs, written by `scripts/extract-docs.rs` to
+ * `docs/examples.json`.
+ *
+ * @property id - `<section id>#example-<n>`
+ * @property section_id - Leaf chunk (or unchunked page) containing the block
+ * @property language - Fence language, or one inferred from the block's caption
+ * @property kind - `input` (uses macros), `expanded` (generated output), `config`,
+ *                  `shell` or `code`
+ * @property file_name - File name from the block's caption (e.g. `vite.config.ts`)
+ * @property heading - Nearest heading above the block
+ * @property context - Last sentence of the prose introducing the block
+ * @property pair_id - ID of the {@link ExamplePair} this block belongs to
+ * @property code - The code itself
+ * @property source_map - Where the block lives in the website sources
+ */
+export interface CodeExample {
+    id: string;
+    section_id: string;
+    language: string;
+    kind: ExampleKind;
+    file_name?: string;
+    heading?: string;
+    context?: string;
+    pair_id?: string;
+    code: string;
+    source_map?: SourceMap;
+}
+
+/** Kind of a code example */
+export type ExampleKind = 'input' | 'expanded' | 'config' | 'shell' | 'code';
+
+/**
+ * An input example linked to the expanded output shown for it in the docs, written
+ * to `docs/example-pairs.json`. Useful as golden tests for `expand-code` and as
+ * few-shot prompts.
+ *
+ * @property id - `<section id>#pair-<n>`
+ * @property section_id - Section containing both examples
+ * @property input - ID of the input {@link CodeExample}
+ * @property output - ID of the expanded {@link CodeExample}
+ * @property macros - Macros named in the input's `@derive(...)`
+ * @property types - Input types that the output expands
+ * @property generated - Names only the output declares (e.g. `User.toString`)
+ * @property verified - True if both samples parsed and the output expands the input
+ */
+export interface ExamplePair {
+    id: string;
+    section_id: string;
+    input: string;
+    output: string;
+    macros: string[];
+    types: string[];
+    generated: string[];
+    verified: boolean;
+}
+
+/**
+ * A built-in macro as documented on its builtin-macros page, written to
+ * `docs/macros.json`. Used by `get-macro-info` when the native bindings are missing.
+ *
+ * @property name - Name used in `@derive(...)`, e.g. "Debug"
+ * @property section_id - ID of the macro's page
+ * @property description - Summary of the macro's page
+ * @property decorator - Field decorator configuring the macro, e.g. "@serde"
+ * @property targets - Declaration kinds the macro can be applied to
+ * @property generated - Methods and functions the macro generates
+ * @property options - Decorator options the macro accepts
+ * @property example_pairs - IDs of {@link ExamplePair}s on the macro's page
+ */
+export interface MacroInfo {
+    name: string;
+    section_id: string;
+    description: string;
+    decorator?: string;
+    targets: MacroTarget[];
+    generated: GeneratedMember[];
+    options: MacroOption[];
+    example_pairs: string[];
+}
+
+/** Declaration kind a macro can be applied to */
+export type MacroTarget = 'class' | 'interface' | 'enum' | 'type alias';
+
+/**
+ * A method or standalone function generated by a macro.
+ *
+ * @property name - Documented name; functions keep their placeholder prefix (`classNameClone`)
+ * @property kind - Instance method, static method or standalone function
+ * @property signature - Signature as written in the docs
+ * @property targets - Declaration kinds it is generated for
+ * @property section_id - Section documenting it
+ */
+export interface GeneratedMember {
+    name: string;
+    kind: 'method' | 'static_method' | 'function';
+    signature: string;
+    targets: MacroTarget[];
+    section_id: string;
+}
+
+/**
+ * A decorator option accepted by a macro.
+ *
+ * @property name - Option name, e.g. "rename"
+ * @property aliases - Other names documented with it, e.g. "skipSerializing"
+ * @property syntax - Forms as written in the docs, e.g. `rename = "jsonKey"`
+ * @property level - Whether it applies to a field or the whole type
+ * @property description - What the option does
+ * @property section_id - Section documenting it
+ */
+export interface MacroOption {
+    name: string;
+    aliases?: string[];
+    syntax: string[];
+    level: 'field' | 'container';
+    description: string;
+    section_id: string;
+}
+
+/**
+ * Options and validators accepted by the `@serde` decorator, written to
+ * `docs/serde.json`.
+ */
+export interface SerdeReference {
+    decorator: string;
+    options: SerdeOption[];
+    validators: SerdeValidator[];
+}
+
+/**
+ * A `@serde` option, merged across the macros that document it.
+ *
+ * @property params - Parameters; empty for flags such as `skip`
+ * @property usages - What the option does for each macro, with the documenting section
+ */
+export interface SerdeOption {
+    name: string;
+    aliases?: string[];
+    syntax: string[];
+    params: SerdeParam[];
+    level: 'field' | 'container';
+    usages: Array<{ macro: string; description: string; section_id: string }>;
+}
+
+/**
+ * A validator usable in `@serde({ validate: ... })`.
+ *
+ * @property field_types - Field types it applies to, e.g. "string" or "Date"
+ * @property category - Validator group from the docs, e.g. "String"
+ */
+export interface SerdeValidator {
+    name: string;
+    params: SerdeParam[];
+    field_types: string[];
+    category: string;
+    description: string;
+    section_id: string;
+}
+
+/**
+ * A parameter of a `@serde` option or validator.
+ *
+ * @property type - "string", "number", "expression", "validator" or "unknown"
+ */
+export interface SerdeParam {
+    name: string;
+    type: string;
+    optional?: boolean;
+    variadic?: boolean;
+}
+
+/**
+ * A `@serde(...)` argument that the `@serde` reference does not know.
+ *
+ * @property level - "warning" when the name is a likely typo of a documented name,
+ *   otherwise "info", since the reference may not list every valid name
+ * @property line - 1-indexed line of the argument
+ * @property column - 1-indexed column of the argument
+ * @property suggestion - Documented name the argument most likely misspells
+ * @property section_id - Section documenting the valid names
+ */
+export interface SerdeIssue {
+    level: 'warning' | 'info';
+    message: string;
+    line: number;
+    column: number;
+    help: string;
+    suggestion?: string;
+    section_id?: string;
+}
+
+/**
+ * The subset of JSON Schema used by `docs/macroforge.schema.json`.
+ *
+ * @property x-section-id - Section documenting the option
+ */
+export interface JsonSchema {
+    type?: string;
+    description?: string;
+    default?: unknown;
+    examples?: unknown[];
+    enum?: unknown[];
+    properties?: Record<string, JsonSchema>;
+    additionalProperties?: boolean | JsonSchema;
+    items?: JsonSchema;
+    anyOf?: JsonSchema[];
+    'x-section-id'?: string;
+}
+
+/**
+ * A `macroforge.json` value that does not match the documented schema.
+ *
+ * @property path - Dotted key path, e.g. "limits.maxMemoryBytes"; empty for the root
+ * @property section_id - Section documenting the option
+ */
+export interface ConfigIssue {
+    path: string;
+    message: string;
+    section_id?: string;
+}
+
+/**
+ * A documented error from `docs/errors.json`.
+ *
+ * @property kind - "diagnostic" (expansion), "condition" (documented cause of an
+ *   expansion error), "runtime" (generated code) or "troubleshooting"
+ * @property pattern - Regular expression matching the message
+ * @property keywords - Code a diagnostic must mention, for entries without a pattern
+ * @property macro - Built-in macro whose page documents the error
+ */
+export interface ErrorEntry {
+    id: string;
+    kind: 'diagnostic' | 'condition' | 'runtime' | 'troubleshooting';
+    message: string;
+    pattern?: string;
+    keywords?: string[];
+    macro?: string;
+    explanation: string;
+    fixes?: string[];
+    section_ids: string[];
+}
+
+/**
+ * A section and its similarity to a query.
+ */
+export interface SimilarSection {
+    section: Section;
+    score: number;
 }
 
 /**
@@ -115,14 +521,571 @@ export function loadSections(): Section[] {
     return sectionsData;
 }
 
+/**
+ * Loads the precomputed search index from `docs/search-index.json`.
+ *
+ * The index is read once and cached. Older docs builds without an index fall back
+ * to substring scoring in {@link searchSections}.
+ *
+ * @returns The parsed index, or null if the file does not exist
+ */
+export function loadSearchIndex(): SearchIndex | null {
+    if (cachedSearchIndex !== undefined) {
+        return cachedSearchIndex;
+    }
+
+    const indexPath = join(docsDir, 'search-index.json');
+    cachedSearchIndex = existsSync(indexPath)
+        ? (JSON.parse(readFileSync(indexPath, 'utf-8')) as SearchIndex)
+        : null;
+    return cachedSearchIndex;
+}
+
+/**
+ * Tokenizes text the same way the extractor builds the search index.
+ *
+ * Alphanumeric runs are lowercased and stemmed, and identifiers also contribute
+ * their parts, so `serializeWithContext` yields `serializewithcontext`, `serializ`
+ * and `context`. Stop words and single characters are dropped.
+ *
+ * @param text - Text to tokenize
+ * @param stopWords - Words to drop, usually {@link SearchIndex.stop_words}
+ * @returns Index terms in order of appearance
+ */
+export function tokenize(text: string, stopWords: ReadonlySet<string>): string[] {
+    const terms: string[] = [];
+    const push = (word: string) => {
+        if (word.length > 1 && !stopWords.has(word)) {
+            terms.push(stemWord(word));
+        }
+    };
+
+    for (const [token] of text.matchAll(/[A-Za-z0-9_]+/g)) {
+        push(token.toLowerCase());
+        const parts = identifierParts(token);
+        if (parts.length > 1) {
+            parts.forEach(push);
+        }
+    }
+    return terms;
+}
+
+/**
+ * Loads the precomputed section vectors from `docs/vectors.json`.
+ *
+ * The vectors are read once and cached.
+ *
+ * @returns The parsed vectors, or null if the file does not exist
+ */
+export function loadSectionVectors(): SectionVectors | null {
+    if (cachedSectionVectors !== undefined) {
+        return cachedSectionVectors;
+    }
+
+    const vectorsPath = join(docsDir, 'vectors.json');
+    cachedSectionVectors = existsSync(vectorsPath)
+        ? (JSON.parse(readFileSync(vectorsPath, 'utf-8')) as SectionVectors)
+        : null;
+    return cachedSectionVectors;
+}
+
+/**
+ * Loads the alias dictionary from `docs/aliases.json`.
+ *
+ * The dictionary is read once and cached.
+ *
+ * @returns The parsed dictionary, or null if the file does not exist
+ */
+export function loadAliases(): AliasDictionary | null {
+    if (cachedAliases !== undefined) {
+        return cachedAliases;
+    }
+
+    const aliasesPath = join(docsDir, 'aliases.json');
+    cachedAliases = existsSync(aliasesPath)
+        ? (JSON.parse(readFileSync(aliasesPath, 'utf-8')) as AliasDictionary)
+        : null;
+    return cachedAliases;
+}
+
+/**
+ * Resolves a name through the alias dictionary.
+ *
+ * @param query - A title, identifier, synonym or old section ID in any spelling
+ * @returns Matching section IDs, most specific first (empty if unknown)
+ *
+ * @example
+ * ```typescript
+ * resolveAlias('@derive(PartialEq)'); // ['partial-eq']
+ * resolveAlias('equals');             // ['partial-eq']
+ * ```
+ */
+export function resolveAlias(query: string): string[] {
+    const key = query.trim().toLowerCase().replace(/\s+/g, ' ');
+    return loadAliases()?.aliases[key]?.ids ?? [];
+}
+
+/**
+ * Loads the code example catalog from `docs/examples.json`.
+ *
+ * The catalog is read once and cached.
+ *
+ * @returns All code examples, or an empty array if the file does not exist
+ */
+export function loadExamples(): CodeExample[] {
+    if (cachedExamples !== undefined) {
+        return cachedExamples;
+    }
+
+    const examplesPath = join(docsDir, 'examples.json');
+    cachedExamples = existsSync(examplesPath)
+        ? (JSON.parse(readFileSync(examplesPath, 'utf-8')) as CodeExample[])
+        : [];
+    return cachedExamples;
+}
+
+/**
+ * Loads the before/after example pairs from `docs/example-pairs.json`.
+ *
+ * @returns All example pairs, or an empty array if the file does not exist
+ */
+export function loadExamplePairs(): ExamplePair[] {
+    const pairsPath = join(docsDir, 'example-pairs.json');
+    return existsSync(pairsPath)
+        ? (JSON.parse(readFileSync(pairsPath, 'utf-8')) as ExamplePair[])
+        : [];
+}
+
+/**
+ * Loads the built-in macro catalog from `docs/macros.json`.
+ *
+ * The catalog is read once and cached.
+ *
+ * @returns All documented macros, or an empty array if the file does not exist
+ */
+export function loadMacros(): MacroInfo[] {
+    if (cachedMacros !== undefined) {
+        return cachedMacros;
+    }
+
+    const macrosPath = join(docsDir, 'macros.json');
+    cachedMacros = existsSync(macrosPath)
+        ? (JSON.parse(readFileSync(macrosPath, 'utf-8')) as MacroInfo[])
+        : [];
+    return cachedMacros;
+}
+
+/**
+ * Finds catalogued macros by macro name, section ID or decorator.
+ *
+ * @param name - e.g. "Serialize", "partial-eq", "serde" or "@serde"
+ * @returns Matching macros; a decorator can match several (`@serde`)
+ */
+export function findMacros(name: string): MacroInfo[] {
+    const key = name.toLowerCase().replace(/^@derive\((.*)\)$/, '$1').replace(/^@/, '');
+    return loadMacros().filter(
+        (m) =>
+            m.name.toLowerCase() === key ||
+            m.section_id === key ||
+            m.decorator?.toLowerCase() === `@${key}`
+    );
+}
+
+/**
+ * Loads the `@serde` option and validator reference from `docs/serde.json`.
+ *
+ * The reference is read once and cached.
+ *
+ * @returns The reference, or null if the file does not exist
+ */
+export function loadSerdeReference(): SerdeReference | null {
+    if (cachedSerdeReference !== undefined) {
+        return cachedSerdeReference;
+    }
+
+    const referencePath = join(docsDir, 'serde.json');
+    cachedSerdeReference = existsSync(referencePath)
+        ? (JSON.parse(readFileSync(referencePath, 'utf-8')) as SerdeReference)
+        : null;
+    return cachedSerdeReference;
+}
+
+/**
+ * Checks the option and validator names used in `@serde(...)` decorators against
+ * the `@serde` reference. Both the object form (`@serde({ rename: "id" })`) and the
+ * call form (`@serde(skip, validate(email))`) are understood. Names close to a
+ * documented name are reported as likely typos; other unknown names only as info.
+ *
+ * @param code - TypeScript source to check
+ * @returns One issue per unknown option or validator; empty without a reference
+ */
+export function checkSerdeUsage(code: string): SerdeIssue[] {
+    const reference = loadSerdeReference();
+    if (!reference) {
+        return [];
+    }
+
+    const optionNames = new Set(reference.options.flatMap((o) => [o.name, ...(o.aliases ?? [])]));
+    const validatorNames = new Set(reference.validators.map((v) => v.name));
+    const optionSection = reference.options[0]?.usages[0]?.section_id;
+    const validatorSection = reference.validators[0]?.section_id;
+    const issues: SerdeIssue[] = [];
+
+    const report = (
+        offset: number,
+        kind: 'option' | 'validator',
+        name: string,
+        known: Set<string>,
+        sectionId?: string
+    ) => {
+        const before = code.slice(0, offset).split('\n');
+        const location = { line: before.length, column: before[before.length - 1].length + 1 };
+        const suggestion = closestName(name, known);
+        issues.push(
+            suggestion
+                ? {
+                    level: 'warning',
+                    message: `Unknown @serde ${kind} \`${name}\`; did you mean \`${suggestion}\`?`,
+                    ...location,
+                    help: `Replace \`${name}\` with \`${suggestion}\``,
+                    suggestion,
+                    section_id: sectionId
+                }
+                : {
+                    level: 'info',
+                    message: `@serde ${kind} \`${name}\` is not in the documented ${kind}s`,
+                    ...location,
+                    help: `Documented ${kind}s: ${[...known].join(', ')}`,
+                    section_id: sectionId
+                }
+        );
+    };
+
+    for (const match of code.matchAll(/@serde\(/g)) {
+        const start = (match.index ?? 0) + match[0].length;
+        const args = enclosed(code, start - 1);
+        for (const [name, value, offset] of decoratorArgs(args, start)) {
+            if (!optionNames.has(name)) {
+                report(offset, 'option', name, optionNames, optionSection);
+            } else if (name === 'validate' && value) {
+                for (const [validator, , at] of decoratorArgs(value.text, value.offset)) {
+                    if (!validatorNames.has(validator)) {
+                        report(at, 'validator', validator, validatorNames, validatorSection);
+                    }
+                }
+            }
+        }
+    }
+
+    return issues;
+}
+
+/**
+ * Finds the known name that `name` most likely misspells: one within two edits,
+ * or one edit for names of up to four characters.
+ */
+function closestName(name: string, known: Iterable<string>): string | undefined {
+    const limit = name.length <= 4 ? 1 : 2;
+    let best: string | undefined;
+    let bestDistance = limit + 1;
+    for (const candidate of known) {
+        const distance = editDistance(name.toLowerCase(), candidate.toLowerCase());
+        if (distance < bestDistance) {
+            best = candidate;
+            bestDistance = distance;
+        }
+    }
+    return best;
+}
+
+/** Levenshtein distance between two strings. */
+function editDistance(a: string, b: string): number {
+    let previous = Array.from({ length: b.length + 1 }, (_, j) => j);
+    for (let i = 1; i <= a.length; i++) {
+        const current = [i];
+        for (let j = 1; j <= b.length; j++) {
+            const substitution = previous[j - 1] + (a[i - 1] === b[j - 1] ? 0 : 1);
+            current.push(Math.min(previous[j] + 1, current[j - 1] + 1, substitution));
+        }
+        previous = current;
+    }
+    return previous[b.length];
+}
+
+/**
+ * Loads the `macroforge.json` JSON Schema from `docs/macroforge.schema.json`.
+ *
+ * The schema is read once and cached.
+ *
+ * @returns The schema, or null if the file does not exist
+ */
+export function loadConfigSchema(): JsonSchema | null {
+    if (cachedConfigSchema !== undefined) {
+        return cachedConfigSchema;
+    }
+
+    const schemaPath = join(docsDir, 'macroforge.schema.json');
+    cachedConfigSchema = existsSync(schemaPath)
+        ? (JSON.parse(readFileSync(schemaPath, 'utf-8')) as JsonSchema)
+        : null;
+    return cachedConfigSchema;
+}
+
+/**
+ * Validates a parsed `macroforge.json` against the documented schema. Unknown keys,
+ * wrong types and values outside an `enum` are reported.
+ *
+ * @param config - Parsed configuration
+ * @returns One issue per mismatch; empty without a schema
+ */
+export function validateConfig(config: unknown): ConfigIssue[] {
+    const schema = loadConfigSchema();
+    const issues: ConfigIssue[] = [];
+    if (schema) {
+        schemaIssues(config, schema, [], issues);
+    }
+    return issues;
+}
+
+/**
+ * Loads the error catalog from `docs/errors.json`.
+ *
+ * The catalog is read once and cached.
+ *
+ * @returns All documented errors, or an empty array if the file does not exist
+ */
+export function loadErrorCatalog(): ErrorEntry[] {
+    if (cachedErrors !== undefined) {
+        return cachedErrors;
+    }
+
+    const errorsPath = join(docsDir, 'errors.json');
+    cachedErrors = existsSync(errorsPath)
+        ? (JSON.parse(readFileSync(errorsPath, 'utf-8')) as ErrorEntry[])
+        : [];
+    return cachedErrors;
+}
+
+/**
+ * Finds the documented error behind a diagnostic message.
+ *
+ * Entries whose pattern matches win, the most specific pattern first. Otherwise a
+ * condition matches when the message mentions all of its keywords; among those, the
+ * one sharing the most words with the message is chosen.
+ *
+ * @param message - Diagnostic message, e.g. "Unknown derive macro: Foo"
+ * @returns The matching entry, or undefined if none matches
+ *
+ * @example
+ * ```typescript
+ * const entry = explainDiagnostic('Field `theme` has no @default');
+ * // entry?.section_ids → ['default']
+ * ```
+ */
+export function explainDiagnostic(message: string): ErrorEntry | undefined {
+    const catalog = loadErrorCatalog();
+    const lower = message.toLowerCase();
+
+    const matched = errorPatterns().find(({ regex }) => regex.test(message));
+    if (matched) {
+        return matched.entry;
+    }
+
+    const words = new Set(tokenize(message, new Set()));
+    let best: ErrorEntry | undefined;
+    let bestScore = 0;
+    for (const entry of catalog) {
+        const keywords = entry.keywords ?? [];
+        if (keywords.length === 0 || !keywords.every((k) => lower.includes(k.toLowerCase()))) {
+            continue;
+        }
+        const score = 1 + tokenize(entry.message, new Set()).filter((w) => words.has(w)).length;
+        if (score > bestScore) {
+            best = entry;
+            bestScore = score;
+        }
+    }
+    return best;
+}
+
+/**
+ * Compiles the patterns of the error catalog once, longest (most specific) first.
+ * Patterns that are not valid JavaScript regular expressions are skipped.
+ */
+function errorPatterns(): Array<{ entry: ErrorEntry; regex: RegExp }> {
+    if (cachedErrorPatterns !== undefined) {
+        return cachedErrorPatterns;
+    }
+
+    cachedErrorPatterns = [];
+    for (const entry of loadErrorCatalog()) {
+        if (!entry.pattern) {
+            continue;
+        }
+        try {
+            cachedErrorPatterns.push({ entry, regex: new RegExp(entry.pattern, 'i') });
+        } catch {
+            // Leave the entry to keyword matching
+        }
+    }
+    cachedErrorPatterns.sort((a, b) => b.entry.pattern!.length - a.entry.pattern!.length);
+    return cachedErrorPatterns;
+}
+
+/**
+ * Finds code examples matching a query.
+ *
+ * Query terms are matched (with the search index tokenizer) against the example's
+ * section ID and heading (+3 each), introducing sentence (+2) and code (+1).
+ *
+ * @param query - Free-text query, e.g. "serialize with rename"
+ * @param filters - Optional kind and language the examples must have
+ * @param limit - Maximum number of results
+ * @returns Matching examples, best first
+ *
+ * @example
+ * ```typescript
+ * const configs = findExamples('vite', { kind: 'config' });
+ * ```
+ */
+export function findExamples(
+    query: string,
+    filters: { kind?: ExampleKind; language?: string } = {},
+    limit = 5
+): CodeExample[] {
+    const stopWords = new Set(loadSearchIndex()?.stop_words ?? []);
+    const terms = new Set(tokenize(query, stopWords));
+    const language = filters.language?.toLowerCase();
+
+    const count = (text: string | undefined) => {
+        const tokens = new Set(tokenize(text ?? '', stopWords));
+        return [...terms].filter((t) => tokens.has(t)).length;
+    };
+
+    return loadExamples()
+        .filter((e) => !filters.kind || e.kind === filters.kind)
+        .filter((e) => !language || e.language === language)
+        .map((example) => ({
+            example,
+            score: 3 * count(example.section_id) + 3 * count(example.heading) +
+                2 * count(example.context) + count(example.code)
+        }))
+        .filter((r) => r.score > 0 || terms.size === 0)
+        .sort((a, b) => b.score - a.score)
+        .slice(0, limit)
+        .map((r) => r.example);
+}
+
+/**
+ * Embeds free text in the same hashed TF-IDF space as the section vectors.
+ *
+ * @param vectors - Section vectors loaded with {@link loadSectionVectors}
+ * @param text - Query text
+ * @param stopWords - Words to drop, usually {@link SearchIndex.stop_words}
+ * @returns Unit-length sparse vector keyed by bucket (empty if no term survives)
+ */
+export function embedQuery(
+    vectors: SectionVectors,
+    text: string,
+    stopWords: ReadonlySet<string>
+): Map<number, number> {
+    const counts = new Map<number, number>();
+    for (const term of tokenize(text, stopWords)) {
+        const features =
+            /^[\x00-\x7f]*$/.test(term) && term.length > vectors.prefix_length
+                ? [`~${term.slice(0, vectors.prefix_length)}`, term]
+                : [term];
+        for (const feature of features) {
+            const bucket = featureBucket(feature, vectors.dimensions);
+            counts.set(bucket, (counts.get(bucket) ?? 0) + 1);
+        }
+    }
+
+    const embedding = new Map<number, number>();
+    for (const [bucket, tf] of counts) {
+        embedding.set(bucket, (1 + Math.log(tf)) * vectors.idf[bucket]);
+    }
+    const norm = Math.sqrt([...embedding.values()].reduce((sum, w) => sum + w * w, 0));
+    if (norm === 0) {
+        return embedding;
+    }
+    for (const [bucket, weight] of embedding) {
+        embedding.set(bucket, weight / norm);
+    }
+    return embedding;
+}
+
+/**
+ * Cosine similarity between a query embedding and a stored section vector. Both are
+ * unit length, so this is their dot product.
+ *
+ * @param query - Embedding from {@link embedQuery}
+ * @param vector - Stored sparse section vector
+ * @returns Similarity in [0, 1]
+ */
+export function cosineSimilarity(
+    query: Map<number, number>,
+    vector: { buckets: number[]; weights: number[] }
+): number {
+    let dot = 0;
+    vector.buckets.forEach((bucket, i) => {
+        dot += (query.get(bucket) ?? 0) * vector.weights[i];
+    });
+    return dot;
+}
+
+/**
+ * Finds the sections most similar to a query using the precomputed vectors.
+ *
+ * Useful for paraphrased questions that share few exact keywords with the docs.
+ *
+ * @param sections - Sections to return results from
+ * @param query - Free-text query
+ * @param limit - Maximum number of results
+ * @returns Sections with similarity of at least {@link MIN_SIMILARITY}, most similar
+ *          first, or an empty array if no vectors are available
+ *
+ * @example
+ * ```typescript
+ * const similar = findSimilarSections(sections, 'how do I compare two objects', 3);
+ * // [{ section: partial-eq, score: 0.31 }, ...]
+ * ```
+ */
+export function findSimilarSections(
+    sections: Section[],
+    query: string,
+    limit = 10
+): SimilarSection[] {
+    const vectors = loadSectionVectors();
+    if (!vectors) {
+        return [];
+    }
+
+    const stopWords = new Set(loadSearchIndex()?.stop_words ?? []);
+    const embedding = embedQuery(vectors, query, stopWords);
+    const byId = new Map(sections.map((s) => [s.id, s]));
+
+    return vectors.vectors
+        .map((vector, doc) => ({
+            section: byId.get(vectors.docs[doc]),
+            score: cosineSimilarity(embedding, vector)
+        }))
+        .filter(
+            (r): r is SimilarSection => r.section !== undefined && r.score >= MIN_SIMILARITY
+        )
+        .sort((a, b) => b.score - a.score)
+        .slice(0, limit);
+}
+
 /**
  * Finds a section by ID or title using progressive matching strategies.
  *
  * The function attempts to match in the following order (stopping at first match):
  * 1. Exact ID match (case-insensitive)
  * 2. Exact title match (case-insensitive)
- * 3. Partial ID match (query is substring of ID)
- * 4. Partial title match (query is substring of title)
+ * 3. Alias match (spelling variants, synonyms and renamed IDs, see {@link resolveAlias})
+ * 4. Partial ID match (query is substring of ID)
+ * 5. Partial title match (query is substring of title)
  *
  * @param sections - Array of sections to search through
  * @param query - Search query (ID or title to find)
@@ -132,6 +1095,7 @@ export function loadSections(): Section[] {
  * ```typescript
  * const debug = getSection(sections, 'debug');        // Exact ID match
  * const serde = getSection(sections, 'Serialize');    // Exact title match
+ * const eq = getSection(sections, 'equals');          // Alias for "partial-eq"
  * const partial = getSection(sections, 'valid');      // Partial match on "validators"
  * ```
  */
@@ -149,11 +1113,17 @@ export function getSection(
     match = sections.find((s) => s.title.toLowerCase() === normalizedQuery);
     if (match) return match;
 
-    // Priority 3: Partial ID match (query is substring of ID)
+    // Priority 3: Alias dictionary (first alias target that is present)
+    for (const id of resolveAlias(query)) {
+        match = sections.find((s) => s.id === id);
+        if (match) return match;
+    }
+
+    // Priority 4: Partial ID match (query is substring of ID)
     match = sections.find((s) => s.id.toLowerCase().includes(normalizedQuery));
     if (match) return match;
 
-    // Priority 4: Partial title match (query is substring of title)
+    // Priority 5: Partial title match (query is substring of title)
     match = sections.find((s) => s.title.toLowerCase().includes(normalizedQuery));
     if (match) return match;
 
@@ -191,12 +1161,16 @@ export function getSections(sections: Section[], queries: string[]): Section[] {
 }
 
 /**
- * Performs a fuzzy search across sections using a weighted scoring algorithm.
+ * Performs a fuzzy search across sections, most relevant first.
  *
- * The search splits the query into keywords and scores each section based on
- * where matches are found. Results are sorted by relevance score (highest first).
+ * When `docs/search-index.json` is available, sections are ranked with BM25F over
+ * their title, headings, keywords and body (see {@link rankWithIndex}). If section
+ * vectors are available too, that ranking is fused with cosine similarity (see
+ * {@link findSimilarSections}) by reciprocal rank, so paraphrased queries still find
+ * the right page. Without an index, or when nothing matches, the substring scoring
+ * below is used.
  *
- * ## Scoring Algorithm
+ * ## Fallback Scoring Algorithm
  *
  * Each section is scored based on matches in different fields:
  * - **ID match** (+10): Full query found in section ID
@@ -221,6 +1195,17 @@ export function getSections(sections: Section[], queries: string[]): Section[] {
  * ```
  */
 export function searchSections(sections: Section[], query: string): Section[] {
+    const index = loadSearchIndex();
+    if (index) {
+        const ranked = fuseRankings([
+            rankWithIndex(sections, index, query),
+            findSimilarSections(sections, query, sections.length).map((r) => r.section)
+        ]);
+        if (ranked.length > 0) {
+            return ranked;
+        }
+    }
+
     const normalizedQuery = query.toLowerCase().trim();
     const keywords = normalizedQuery.split(/\s+/);
 
@@ -266,6 +1251,278 @@ export function searchSections(sections: Section[], query: string): Section[] {
         .map((s) => s.section);
 }
 
+/**
+ * Ranks sections against a query with BM25F using the precomputed index.
+ *
+ * Each field's term frequency is length-normalized against that field's average
+ * length and weighted, then the combined frequency is saturated with `k1`:
+ *
+ *   score = Σ idf(t) · tf'(t) · (k1 + 1) / (k1 + tf'(t))
+ *   tf'(t) = Σ_f weight_f · tf_f / (1 − b + b · len_f / avg_len_f)
+ *
+ * @param sections - Sections to return results from (index entries not in this list are ignored)
+ * @param index - Search index loaded with {@link loadSearchIndex}
+ * @param query - Free-text query
+ * @returns Matching sections sorted by score (highest first)
+ */
+export function rankWithIndex(
+    sections: Section[],
+    index: SearchIndex,
+    query: string
+): Section[] {
+    const byId = new Map(sections.map((s) => [s.id, s]));
+    const stride = index.fields.length + 1;
+    const docCount = index.docs.length;
+    const scores = new Map<number, number>();
+
+    for (const term of new Set(tokenize(query, new Set(index.stop_words)))) {
+        const postings = index.postings[term];
+        if (!postings) continue;
+
+        const df = postings.length / stride;
+        const idf = Math.log(1 + (docCount - df + 0.5) / (df + 0.5));
+
+        for (let i = 0; i < postings.length; i += stride) {
+            const doc = postings[i];
+            let tf = 0;
+            for (let f = 0; f < index.fields.length; f++) {
+                const avg = index.avg_lengths[f] || 1;
+                const norm = 1 - index.b + (index.b * index.lengths[doc][f]) / avg;
+                tf += (index.weights[f] * postings[i + 1 + f]) / norm;
+            }
+            const score = (idf * tf * (index.k1 + 1)) / (index.k1 + tf);
+            scores.set(doc, (scores.get(doc) ?? 0) + score);
+        }
+    }
+
+    return [...scores.entries()]
+        .sort((a, b) => b[1] - a[1])
+        .map(([doc]) => byId.get(index.docs[doc]))
+        .filter((s): s is Section => s !== undefined);
+}
+
+/**
+ * Merges rankings with reciprocal rank fusion: each section scores
+ * `Σ 1 / (RANK_FUSION_K + rank)` over the rankings it appears in.
+ */
+function fuseRankings(rankings: Section[][]): Section[] {
+    const scores = new Map<Section, number>();
+    for (const ranking of rankings) {
+        ranking.forEach((section, rank) => {
+            scores.set(section, (scores.get(section) ?? 0) + 1 / (RANK_FUSION_K + rank + 1));
+        });
+    }
+    return [...scores.entries()].sort((a, b) => b[1] - a[1]).map(([section]) => section);
+}
+
+/**
+ * 32-bit FNV-1a over UTF-8 bytes, matching the extractor's feature hashing.
+ */
+function featureBucket(feature: string, dimensions: number): number {
+    let hash = 0x811c9dc5;
+    for (const byte of new TextEncoder().encode(feature)) {
+        hash ^= byte;
+        hash = Math.imul(hash, 0x01000193) >>> 0;
+    }
+    return hash % dimensions;
+}
+
+/**
+ * Splits an identifier such as `fromJSON` or `ts_macro_derive` into lowercase words,
+ * matching the extractor's identifier splitting.
+ */
+function identifierParts(token: string): string[] {
+    const parts: string[] = [];
+    let current = '';
+    const chars = [...token];
+    const isLower = (c: string | undefined) => !!c && c !== c.toUpperCase();
+    const isUpper = (c: string | undefined) => !!c && c !== c.toLowerCase();
+
+    chars.forEach((c, i) => {
+        if (!/[\p{L}\p{N}]/u.test(c)) {
+            parts.push(current);
+            current = '';
+            return;
+        }
+        const prev = chars[i - 1];
+        const boundary =
+            isUpper(c) && (isLower(prev) || (isUpper(prev) && isLower(chars[i + 1])));
+        if (boundary) {
+            parts.push(current);
+            current = '';
+        }
+        current += c.toLowerCase();
+    });
+    parts.push(current);
+    return parts.filter((p) => p.length > 0);
+}
+
+/**
+ * Light suffix stripping matching the extractor, so `macros`/`macro` and
+ * `caching`/`cache` share a stem. Words with non-letters are left untouched.
+ */
+function stemWord(word: string): string {
+    if (!/^[a-z]+$/.test(word)) {
+        return word;
+    }
+    if (word.endsWith('ies') && word.length - 3 >= 3) {
+        return `${word.slice(0, -3)}y`;
+    }
+    for (const suffix of ['ing', 'ed', 'es', 'e', 's']) {
+        if (word.endsWith(suffix)) {
+            const base = word.slice(0, -suffix.length);
+            if (base.length >= 3 && !(suffix === 's' && base.endsWith('s'))) {
+                return base;
+            }
+        }
+    }
+    return word;
+}
+
+/**
+ * Collects the places where `value` does not match `schema` into `issues`. Values
+ * without a documenting section of their own are attributed to their parent's.
+ */
+function schemaIssues(
+    value: unknown,
+    schema: JsonSchema,
+    path: string[],
+    issues: ConfigIssue[],
+    parentSection?: string
+): void {
+    const section = schema['x-section-id'] ?? parentSection;
+    const report = (message: string) =>
+        issues.push({ path: path.join('.'), message, section_id: section });
+
+    if (schema.anyOf) {
+        const matches = schema.anyOf.some((s) => {
+            const nested: ConfigIssue[] = [];
+            schemaIssues(value, s, path, nested, section);
+            return nested.length === 0;
+        });
+        if (!matches) {
+            const types = schema.anyOf.map((s) => s.type ?? 'any').join(' | ');
+            report(`Expected ${types}`);
+        }
+        return;
+    }
+
+    const actual = Array.isArray(value) ? 'array' : value === null ? 'null' : typeof value;
+    const expected = schema.type === 'integer' ? 'number' : schema.type;
+    if (expected && actual !== expected) {
+        report(`Expected ${schema.type}, found ${actual}`);
+        return;
+    }
+    if (schema.type === 'integer' && !Number.isInteger(value)) {
+        report(`Expected an integer, found ${value}`);
+    }
+    if (schema.enum && !schema.enum.includes(value)) {
+        report(`Expected one of ${schema.enum.map((v) => JSON.stringify(v)).join(', ')}`);
+    }
+
+    if (Array.isArray(value) && schema.items) {
+        value.forEach((item, i) =>
+            schemaIssues(item, schema.items!, [...path, `${i}`], issues, section)
+        );
+    } else if (actual === 'object') {
+        const properties = schema.properties ?? {};
+        for (const [key, item] of Object.entries(value as Record<string, unknown>)) {
+            const itemSchema = properties[key] ??
+                (typeof schema.additionalProperties === 'object'
+                    ? schema.additionalProperties
+                    : undefined);
+            if (itemSchema) {
+                schemaIssues(item, itemSchema, [...path, key], issues, section);
+            } else if (schema.additionalProperties === false) {
+                const known = Object.keys(properties).join(', ');
+                issues.push({
+                    path: [...path, key].join('.'),
+                    message: `Unknown option \`${key}\`. Known options: ${known}`,
+                    section_id: section ?? Object.values(properties)[0]?.['x-section-id']
+                });
+            }
+        }
+    }
+}
+
+/**
+ * Returns the text between the bracket at `open` and its matching close bracket
+ * (or the end of the code), skipping brackets inside string literals.
+ */
+function enclosed(code: string, open: number): string {
+    let depth = 0;
+    let quote: string | null = null;
+    for (let i = open; i < code.length; i++) {
+        const c = code[i];
+        if (quote) {
+            if (c === '\\') i++;
+            else if (c === quote) quote = null;
+        } else if (c === '"' || c === "'" || c === '`') {
+            quote = c;
+        } else if ('([{'.includes(c)) {
+            depth++;
+        } else if (')]}'.includes(c) && --depth === 0) {
+            return code.slice(open + 1, i);
+        }
+    }
+    return code.slice(open + 1);
+}
+
+/**
+ * Splits decorator arguments into `[name, value, offset]` entries. Accepts
+ * `{ name: value }` objects, `["name"]` arrays, and `name`, `name = value` or
+ * `name(value)` lists. `offset` is the position of `text` within the whole code.
+ */
+function decoratorArgs(
+    text: string,
+    offset: number
+): Array<[string, { text: string; offset: number } | null, number]> {
+    const trimmed = text.trimStart();
+    if (trimmed.startsWith('{') || trimmed.startsWith('[')) {
+        const open = offset + text.length - trimmed.length;
+        return decoratorArgs(enclosed(trimmed, 0), open + 1);
+    }
+
+    const args: Array<[string, { text: string; offset: number } | null, number]> = [];
+    let start = 0;
+    let depth = 0;
+    let quote: string | null = null;
+    const flush = (end: number) => {
+        const part = text.slice(start, end);
+        const match = /^(\s*["'`]?)([A-Za-z_$][\w$]*)["'`]?\s*([:=(]?)/.exec(part);
+        if (match) {
+            const at = offset + start + match[1].length;
+            const valueOffset = offset + start + match[0].length;
+            let value: { text: string; offset: number } | null = null;
+            if (match[3] === '(') {
+                value = { text: enclosed(part, match[0].length - 1), offset: valueOffset };
+            } else if (match[3]) {
+                value = { text: part.slice(match[0].length), offset: valueOffset };
+            }
+            args.push([match[2], value, at]);
+        }
+        start = end + 1;
+    };
+
+    for (let i = 0; i < text.length; i++) {
+        const c = text[i];
+        if (quote) {
+            if (c === '\\') i++;
+            else if (c === quote) quote = null;
+        } else if (c === '"' || c === "'" || c === '`') {
+            quote = c;
+        } else if ('([{'.includes(c)) {
+            depth++;
+        } else if (')]}'.includes(c)) {
+            depth--;
+        } else if (c === ',' && depth === 0) {
+            flush(i);
+        }
+    }
+    flush(text.length);
+    return args;
+}
+
 /**
  * Filters sections by category slug or category title.
  *
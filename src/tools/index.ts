@@ -15,6 +15,8 @@
  * | `macroforge-autofixer` | Validate TypeScript code with @derive decorators |
  * | `expand-code` | Expand macros and show generated code |
  * | `get-macro-info` | Get documentation for macros and decorators |
+ * | `find-examples` | Find code examples from the documentation |
+ * | `validate-config` | Check a macroforge.json against the documented options |
  *
  * ## Architecture
  *
@@ -34,7 +36,23 @@ import {
     ListToolsRequestSchema,
     McpError
 } from '@modelcontextprotocol/sdk/types.js';
-import { getSection, loadSections, searchSections, type Section } from './docs-loader.js';
+import {
+    type CodeExample,
+    checkSerdeUsage,
+    type ExampleKind,
+    explainDiagnostic,
+    findExamples,
+    findMacros,
+    getSection,
+    loadConfigSchema,
+    loadMacros,
+    loadSections,
+    type MacroInfo,
+    searchSections,
+    type Section,
+    type SourceMap,
+    validateConfig
+} from './docs-loader.js';
 
 /** Cached documentation sections loaded at server startup */
 let sections: Section[] = [];
@@ -75,8 +93,10 @@ export function registerTools(server: Server): void {
 
 Returns sections with:
 - title: Section name
+- summary: One or two sentences describing what the section covers
 - use_cases: When this doc is useful (comma-separated keywords)
 - path: File path
+- tokens: Approximate LLM token size of the section (helps budget context)
 - category: Category name
 
 WORKFLOW:
@@ -97,6 +117,7 @@ Example use_cases: "setup, install", "serialization, json", "validation, email"`
 Supports flexible search by:
 - Title (e.g., "Debug", "Vite Plugin")
 - ID (e.g., "debug", "vite-plugin")
+- Alias (e.g., "PartialEq", "@derive(PartialEq)", "equals")
 - Partial matches
 
 Can accept a single section name or an array of sections.
@@ -126,7 +147,8 @@ Returns structured JSON diagnostics with:
 - message: What's wrong
 - location: Line and column number (when available)
 - help: Suggested fix (when available)
-- notes: Additional context (when available)
+- notes: Additional context (when available), including the documentation section
+  explaining a known error
 - summary: Count of errors, warnings, and info messages
 
 This tool MUST be used before sending Macroforge code to the user.
@@ -136,6 +158,7 @@ Detects:
 - Invalid/unknown macro names
 - Malformed @derive decorators
 - @serde validator issues (email, url, length, etc.)
+- Unknown @serde option and validator names, checked against the documentation
 - Macro expansion failures
 - Syntax errors in generated code`,
                     inputSchema: {
@@ -192,7 +215,9 @@ Returns information about:
 - Available macro options and configuration
 
 Use without parameters to get the full manifest of all available macros and decorators.
-Use with a name parameter to get info for a specific macro or decorator.`,
+Use with a name parameter to get info for a specific macro or decorator.
+Without the native bindings, answers come from the documentation's macro catalog, which
+also lists supported targets, generated methods and decorator options.`,
                     inputSchema: {
                         type: 'object',
                         properties: {
@@ -202,6 +227,61 @@ Use with a name parameter to get info for a specific macro or decorator.`,
                             }
                         }
                     }
+                },
+                {
+                    name: 'find-examples',
+                    description: `Finds code examples from the Macroforge documentation.
+
+Each example includes its language, the section it comes from, the nearest heading and
+the sentence introducing it.
+
+Kinds:
+- input: TypeScript using @derive and other macros
+- expanded: Code generated by macro expansion
+- config: Configuration files (macroforge.json, vite.config.ts, ...)
+- shell: Install and CLI commands
+- code: Anything else (e.g. Rust for custom macros)`,
+                    inputSchema: {
+                        type: 'object',
+                        properties: {
+                            query: {
+                                type: 'string',
+                                description: 'What the example should show (e.g., "serde rename", "vite setup")'
+                            },
+                            kind: {
+                                type: 'string',
+                                enum: ['input', 'expanded', 'config', 'shell', 'code'],
+                                description: 'Optional: only return examples of this kind'
+                            },
+                            language: {
+                                type: 'string',
+                                description: 'Optional: only return examples in this language (e.g., "typescript", "rust")'
+                            },
+                            limit: {
+                                type: 'number',
+                                description: 'Maximum number of examples to return (default: 5)'
+                            }
+                        },
+                        required: ['query']
+                    }
+                },
+                {
+                    name: 'validate-config',
+                    description: `Checks a macroforge.json file against the options documented in the configuration
+guide.
+
+Reports unknown options, values of the wrong type and values outside the allowed set,
+each with the documentation section describing the option.`,
+                    inputSchema: {
+                        type: 'object',
+                        properties: {
+                            config: {
+                                type: 'string',
+                                description: 'Contents of the macroforge.json file'
+                            }
+                        },
+                        required: ['config']
+                    }
                 }
             ]
         };
@@ -227,6 +307,14 @@ Use with a name parameter to get info for a specific macro or decorator.`,
             case 'get-macro-info':
                 return handleGetMacroInfo(args as { name?: string });
 
+            case 'find-examples':
+                return handleFindExamples(
+                    args as { query: string; kind?: ExampleKind; language?: string; limit?: number }
+                );
+
+            case 'validate-config':
+                return handleValidateConfig(args as { config: string });
+
             default:
                 throw new McpError(ErrorCode.MethodNotFound, `Unknown tool: ${name}`);
         }
@@ -238,7 +326,7 @@ Use with a name parameter to get info for a specific macro or decorator.`,
  *
  * Returns a formatted list of all available documentation sections, filtering out
  * sub-chunks to show only top-level sections. Each section displays its title,
- * use cases, file path, and category.
+ * summary, use cases, file path, category, and token size when known.
  *
  * Sub-chunks (sections with a `parent_id`) are excluded from this list as they
  * are accessed through their parent section via `get-documentation`.
@@ -252,7 +340,11 @@ function handleListSections() {
     const formatted = topLevelSections
         .map(
             (s) =>
-                `* title: [${s.title}], use_cases: [${s.use_cases}], path: [${s.path}], category: [${s.category_title}]`
+                `* title: [${s.title}]${
+                    s.summary ? `, summary: [${s.summary}]` : ''
+                }, use_cases: [${s.use_cases}], path: [${s.path}], category: [${s.category_title}]${
+                    s.tokens !== undefined ? `, tokens: [${s.tokens}]` : ''
+                }`
         )
         .join('\n');
 
@@ -278,8 +370,13 @@ function handleListSections() {
  * 1. Returns the first chunk's content as the main response
  * 2. Appends a list of additional chunk IDs that can be requested separately
  *
+ * Each returned section ends with a source citation (file and line range) when the
+ * extractor recorded one, so answers can point at the exact page source.
+ *
  * This allows clients to progressively load large documentation without
- * overwhelming context windows.
+ * overwhelming context windows. Chunks can themselves be chunked when a
+ * sub-section is still too large; the first chunk is resolved down to the
+ * first leaf so the response always carries content.
  *
  * @param args - Tool arguments
  * @param args.section - Section name(s) to retrieve (string or array of strings)
@@ -296,12 +393,13 @@ function handleGetDocumentation(args: { section: string | string[] }) {
             if (
                 section.is_chunked && section.chunk_ids && section.chunk_ids.length > 0
             ) {
-                // Get the first chunk
-                const firstChunkId = section.chunk_ids[0];
-                const firstChunk = sections.find((s) => s.id === firstChunkId);
+                // Get the first chunk (descending into nested chunks)
+                const firstChunk = getFirstLeafChunk(section);
 
                 if (firstChunk) {
-                    let result = `# ${section.title}\n\n${firstChunk.content}`;
+                    let result = `# ${section.title}\n\n${firstChunk.content}${
+                        formatSourceCitation(firstChunk)
+                    }`;
 
                     // Add list of other available chunks
                     if (section.chunk_ids.length > 1) {
@@ -328,7 +426,9 @@ function handleGetDocumentation(args: { section: string | string[] }) {
                 }
             } else {
                 // Regular section - return content directly
-                results.push(`# ${section.title}\n\n${section.content}`);
+                results.push(
+                    `# ${section.title}\n\n${section.content}${formatSourceCitation(section)}`
+                );
             }
         } else {
             // Try fuzzy search
@@ -337,9 +437,11 @@ function handleGetDocumentation(args: { section: string | string[] }) {
                 const match = matches[0];
                 // Handle chunked sections in fuzzy match too
                 if (match.is_chunked && match.chunk_ids && match.chunk_ids.length > 0) {
-                    const firstChunk = sections.find((s) => s.id === match.chunk_ids![0]);
+                    const firstChunk = getFirstLeafChunk(match);
                     if (firstChunk) {
-                        let result = `# ${match.title}\n\n${firstChunk.content}`;
+                        let result = `# ${match.title}\n\n${firstChunk.content}${
+                            formatSourceCitation(firstChunk)
+                        }`;
                         if (match.chunk_ids.length > 1) {
                             const otherChunks = match.chunk_ids.slice(1);
                             const chunkList = otherChunks
@@ -361,7 +463,9 @@ function handleGetDocumentation(args: { section: string | string[] }) {
                         results.push(`# ${match.title}\n\n${match.content}`);
                     }
                 } else {
-                    results.push(`# ${match.title}\n\n${match.content}`);
+                    results.push(
+                        `# ${match.title}\n\n${match.content}${formatSourceCitation(match)}`
+                    );
                 }
             } else {
                 results.push(`Documentation for "${name}" not found.`);
@@ -384,7 +488,9 @@ function handleGetDocumentation(args: { section: string | string[] }) {
  *
  * Validates TypeScript code containing @derive decorators using Macroforge's
  * native Rust-based analyzer. Returns structured JSON diagnostics that clients
- * can use to provide error messages and fix suggestions.
+ * can use to provide error messages and fix suggestions. Option and validator
+ * names in `@serde(...)` are also checked against the documentation's `@serde`
+ * reference (`docs/serde.json`) and reported as warnings.
  *
  * ## Diagnostic Levels
  *
@@ -435,21 +541,36 @@ async function handleAutofixer(args: { code: string; filename?: string }) {
 
         const result = macroforge.expandSync(args.code, filename, {});
         const diagnostics = result.diagnostics || [];
+        const serdeIssues = checkSerdeUsage(args.code);
 
         const output: AutofixerResult = {
-            diagnostics: diagnostics.map((d) => ({
-                level: normalizeLevel(d.level),
-                message: d.message,
-                location: d.span
-                    ? { line: d.span.start.line, column: d.span.start.column }
-                    : undefined,
-                help: d.help || undefined,
-                notes: d.notes && d.notes.length > 0 ? d.notes : undefined
-            })),
+            diagnostics: [
+                ...diagnostics.map((d) => {
+                    const docs = documentDiagnostic(d.message, d.help, d.notes);
+                    return {
+                        level: normalizeLevel(d.level),
+                        message: d.message,
+                        location: d.span
+                            ? { line: d.span.start.line, column: d.span.start.column }
+                            : undefined,
+                        help: docs.help,
+                        notes: docs.notes.length > 0 ? docs.notes : undefined
+                    };
+                }),
+                ...serdeIssues.map((issue) => ({
+                    level: issue.level,
+                    message: issue.message,
+                    location: { line: issue.line, column: issue.column },
+                    help: issue.help,
+                    notes: issue.section_id ? [`See section: ${issue.section_id}`] : undefined
+                }))
+            ],
             summary: {
                 errors: diagnostics.filter((d) => d.level === 'Error').length,
-                warnings: diagnostics.filter((d) => d.level === 'Warning').length,
-                info: diagnostics.filter((d) => d.level === 'Info').length
+                warnings: diagnostics.filter((d) => d.level === 'Warning').length +
+                    serdeIssues.filter((issue) => issue.level === 'warning').length,
+                info: diagnostics.filter((d) => d.level === 'Info').length +
+                    serdeIssues.filter((issue) => issue.level === 'info').length
             },
             require_another_tool_call_after_fixing: diagnostics.some((d) => d.level === 'Error')
         };
@@ -486,6 +607,35 @@ async function handleAutofixer(args: { code: string; filename?: string }) {
     }
 }
 
+/**
+ * Adds what the docs say about a diagnostic: the documented fix becomes the help
+ * text when the macro gave none, and the explanation and section are added as notes.
+ *
+ * @param message - Diagnostic message
+ * @param help - Help text from the macro, if any
+ * @param notes - Notes from the macro, if any
+ * @returns Help text and notes to show
+ */
+function documentDiagnostic(
+    message: string,
+    help?: string,
+    notes?: string[]
+): { help?: string; notes: string[] } {
+    const entry = explainDiagnostic(message);
+    if (!entry) {
+        return { help: help || undefined, notes: notes ?? [] };
+    }
+    const sectionId = entry.section_ids[0];
+    return {
+        help: help || entry.fixes?.join(' ') || undefined,
+        notes: [
+            ...(notes ?? []),
+            entry.explanation,
+            ...(sectionId ? [`See section: ${sectionId}`] : [])
+        ]
+    };
+}
+
 /**
  * Handles the `expand-code` tool call.
  *
@@ -536,13 +686,12 @@ async function handleExpandCode(args: { code: string; filename?: string }) {
                     ? ` (line ${d.span.start.line}, col ${d.span.start.column})`
                     : '';
                 text += `- **[${normalizeLevel(d.level)}]**${loc} ${d.message}\n`;
-                if (d.help) {
-                    text += `  - Help: ${d.help}\n`;
+                const docs = documentDiagnostic(d.message, d.help, d.notes);
+                if (docs.help) {
+                    text += `  - Help: ${docs.help}\n`;
                 }
-                if (d.notes && d.notes.length > 0) {
-                    for (const note of d.notes) {
-                        text += `  - Note: ${note}\n`;
-                    }
+                for (const note of docs.notes) {
+                    text += `  - Note: ${note}\n`;
                 }
             }
         }
@@ -568,7 +717,9 @@ async function handleExpandCode(args: { code: string; filename?: string }) {
  *
  * Retrieves documentation for Macroforge macros and field decorators from the
  * native manifest. Can return info for a specific macro/decorator or the full
- * manifest of all available macros and decorators.
+ * manifest of all available macros and decorators. Falls back to the macro
+ * catalog extracted from the documentation (`docs/macros.json`) when the native
+ * bindings are not installed.
  *
  * ## Usage Modes
  *
@@ -589,11 +740,13 @@ async function handleGetMacroInfo(args: { name?: string }) {
         const macroforge = await importMacroforge();
 
         if (!macroforge || !macroforge.__macroforgeGetManifest) {
+            const catalog = formatMacroCatalog(args.name);
             return {
                 content: [
                     {
                         type: 'text' as const,
                         text:
+                            catalog ??
                             'Native Macroforge bindings not available. Install @macroforge/core to access macro documentation.'
                     }
                 ]
@@ -677,6 +830,80 @@ Available decorators: ${manifest.decorators.map((d) => d.export).join(', ')}`
     }
 }
 
+/**
+ * Formats macro documentation from the extracted macro catalog.
+ *
+ * @param name - Optional macro, section ID or decorator name to look up
+ * @returns Formatted documentation, or null if the catalog is not available
+ */
+function formatMacroCatalog(name?: string): string | null {
+    const macros = loadMacros();
+    if (macros.length === 0) {
+        return null;
+    }
+
+    if (!name) {
+        let result = '# Macroforge Macro Catalog\n\n';
+        result += '_From the documentation; install @macroforge/core for the native manifest._\n\n';
+        for (const macro of macros) {
+            result += `### @derive(${macro.name})\n`;
+            result += `${macro.description}\n`;
+            result += `**Targets:** ${macro.targets.join(', ')}\n`;
+            if (macro.decorator) {
+                result += `**Decorator:** \`${macro.decorator}\`\n`;
+            }
+            result += `**Section:** ${macro.section_id}\n\n`;
+        }
+        return result;
+    }
+
+    const matches = findMacros(name);
+    if (matches.length === 0) {
+        return `No macro or decorator found with name "${name}".
+
+Available macros: ${macros.map((m) => m.name).join(', ')}`;
+    }
+
+    return matches.map(formatMacroInfo).join('\n---\n\n');
+}
+
+/**
+ * Formats a catalogued macro with its targets, generated members and options.
+ */
+function formatMacroInfo(macro: MacroInfo): string {
+    let result = `## Macro: @derive(${macro.name})\n\n`;
+    result += `**Description:** ${macro.description}\n`;
+    result += `**Targets:** ${macro.targets.join(', ')}\n`;
+    if (macro.decorator) {
+        result += `**Decorator:** \`${macro.decorator}\`\n`;
+    }
+    result += `**Section:** ${macro.section_id}\n`;
+
+    if (macro.generated.length > 0) {
+        result += '\n### Generated\n\n';
+        for (const member of macro.generated) {
+            const kind = member.kind.replace('_', ' ');
+            result += `- \`${member.signature}\` - ${kind} for ${member.targets.join(', ')}`;
+            result += ` (${member.section_id})\n`;
+        }
+    }
+
+    if (macro.options.length > 0) {
+        result += '\n### Options\n\n';
+        for (const option of macro.options) {
+            const syntax = option.syntax.map((s) => `\`${s}\``).join(' / ');
+            result += `- ${syntax} - ${option.description} [${option.level}]`;
+            result += ` (${option.section_id})\n`;
+        }
+    }
+
+    if (macro.example_pairs.length > 0) {
+        result += `\n**Examples:** ${macro.example_pairs.join(', ')}\n`;
+    }
+
+    return result;
+}
+
 // ============================================================================
 // Types - Match Rust's Diagnostic structure from macroforge_ts_syn/src/abi/patch.rs
 // ============================================================================
@@ -895,6 +1122,139 @@ async function importMacroforge(): Promise<MacroforgeModule | null> {
     }
 }
 
+/**
+ * Handles the `find-examples` tool call.
+ *
+ * Searches the extracted code example catalog and returns each match as a fenced
+ * block with its heading, introducing sentence and source citation.
+ *
+ * @param args - Tool arguments
+ * @param args.query - What the example should show
+ * @param args.kind - Optional example kind filter
+ * @param args.language - Optional language filter
+ * @param args.limit - Maximum number of examples (default: 5)
+ * @returns MCP response with the matching examples as markdown
+ */
+function handleFindExamples(args: {
+    query: string;
+    kind?: ExampleKind;
+    language?: string;
+    limit?: number;
+}) {
+    const examples = findExamples(
+        args.query,
+        { kind: args.kind, language: args.language },
+        args.limit ?? 5
+    );
+
+    const text = examples.length > 0
+        ? examples.map(formatExample).join('\n\n---\n\n')
+        : `No code examples found for "${args.query}".`;
+
+    return {
+        content: [{ type: 'text' as const, text }]
+    };
+}
+
+/**
+ * Handles the `validate-config` tool call.
+ *
+ * Parses the configuration and validates it against `docs/macroforge.schema.json`,
+ * which is generated from the configuration guide.
+ *
+ * @param args - Tool arguments
+ * @param args.config - Contents of a macroforge.json file
+ * @returns MCP response listing each issue with the section documenting the option
+ */
+function handleValidateConfig(args: { config: string }) {
+    const reply = (text: string) => ({ content: [{ type: 'text' as const, text }] });
+
+    if (!loadConfigSchema()) {
+        return reply('The macroforge.json schema is not available. Run the docs extraction first.');
+    }
+
+    let config: unknown;
+    try {
+        config = JSON.parse(args.config);
+    } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        return reply(`macroforge.json is not valid JSON: ${message}`);
+    }
+
+    const issues = validateConfig(config);
+    if (issues.length === 0) {
+        return reply('macroforge.json matches the documented options.');
+    }
+
+    const lines = issues.map((issue) => {
+        const path = issue.path ? `\`${issue.path}\`: ` : '';
+        const see = issue.section_id ? ` (see section: ${issue.section_id})` : '';
+        return `- ${path}${issue.message}${see}`;
+    });
+    return reply(`Found ${issues.length} issue(s) in macroforge.json:\n\n${lines.join('\n')}`);
+}
+
+/**
+ * Formats a code example as markdown with its provenance.
+ *
+ * @param example - Example to format
+ * @returns Markdown heading, introduction, fenced code and source citation
+ */
+function formatExample(example: CodeExample): string {
+    const title = example.heading
+        ? `### ${example.heading} (\`${example.section_id}\`)`
+        : `### \`${example.section_id}\``;
+    const details = [
+        `kind: ${example.kind}`,
+        example.file_name ? `file: ${example.file_name}` : undefined,
+        example.pair_id ? `pair: ${example.pair_id}` : undefined
+    ].filter(Boolean).join(', ');
+    const intro = example.context ? `${example.context}\n\n` : '';
+
+    return `${title}\n\n_${details}_\n\n${intro}\`\`\`${example.language}\n${example.code}\n\`\`\`${
+        formatSourceCitation(example)
+    }`;
+}
+
+/**
+ * Formats a section's source location as a trailing citation line.
+ *
+ * Uses `file:start-end` for mdsvex sources and `file (node path)` for pages
+ * extracted from prerendered HTML.
+ *
+ * @param section - Section or example whose source map should be cited
+ * @returns Citation suffix, or an empty string if no source map is available
+ */
+function formatSourceCitation(section: { source_map?: SourceMap }): string {
+    const map = section.source_map;
+    if (!map) return '';
+
+    const location = map.lines
+        ? `${map.file}:${map.lines[0]}-${map.lines[1]}`
+        : map.node_path
+        ? `${map.file} (${map.node_path})`
+        : map.file;
+    return `\n\n_Source: ${location}_`;
+}
+
+/**
+ * Resolves the first content-bearing chunk of a chunked section.
+ *
+ * Chunks that were split again (e.g. `deserialize/validation`) are chunked
+ * parents themselves, so this follows `chunk_ids[0]` until it reaches a leaf.
+ *
+ * @param section - A chunked section
+ * @returns The first leaf chunk, or undefined if the chain is broken
+ */
+function getFirstLeafChunk(section: Section): Section | undefined {
+    let current: Section | undefined = section;
+    while (current?.is_chunked && current.chunk_ids && current.chunk_ids.length > 0) {
+        const firstChunkId: string = current.chunk_ids[0];
+        current = sections.find((s) => s.id === firstChunkId);
+    }
+    return current === section ? undefined : current;
+}
+
 /**
  * Normalizes a diagnostic level string to lowercase.
  *
@@ -0,0 +1,307 @@
+/**
+ * Tests for the docs loader against fixture data files
+ * Runs against the compiled module in dist/, pointed at a temporary docs directory
+ */
+
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import { mkdtempSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+
+const docsDir = mkdtempSync(join(tmpdir(), 'macroforge-docs-'));
+
+/** Writes a fixture file into the temporary docs directory */
+function writeDoc(name, data) {
+    writeFileSync(join(docsDir, name), JSON.stringify(data));
+}
+
+writeDoc('serde.json', {
+    decorator: '@serde',
+    options: [
+        {
+            name: 'rename',
+            syntax: ['rename = "name"'],
+            params: [{ name: 'name', type: 'string' }],
+            level: 'field',
+            usages: [{ macro: 'Serialize', description: 'Rename the key', section_id: 'serialize' }]
+        },
+        {
+            name: 'skip',
+            aliases: ['skipSerializing'],
+            syntax: ['skip'],
+            params: [],
+            level: 'field',
+            usages: [{ macro: 'Serialize', description: 'Skip the field', section_id: 'serialize' }]
+        },
+        {
+            name: 'validate',
+            syntax: ['validate(...)'],
+            params: [{ name: 'validators', type: 'validator', variadic: true }],
+            level: 'field',
+            usages: [{ macro: 'Deserialize', description: 'Validate', section_id: 'deserialize' }]
+        }
+    ],
+    validators: [
+        {
+            name: 'email',
+            params: [],
+            field_types: ['string'],
+            category: 'String',
+            description: 'Format validation',
+            section_id: 'deserialize/validation'
+        }
+    ]
+});
+
+writeDoc('errors.json', [
+    {
+        id: 'cli#error-1',
+        kind: 'diagnostic',
+        message: 'Unknown derive macro: InvalidMacro',
+        pattern: '^Unknown derive macro: \\w+$',
+        explanation: 'The macro is not registered',
+        section_ids: ['cli']
+    },
+    {
+        id: 'broken#error-1',
+        kind: 'diagnostic',
+        message: 'Broken pattern',
+        pattern: '(unclosed',
+        explanation: 'Never matches',
+        section_ids: ['broken']
+    },
+    {
+        id: 'default#error-1',
+        kind: 'condition',
+        message: 'An enum has no variant marked with `@default`',
+        keywords: ['@default'],
+        explanation: 'Default fails if an enum has no variant marked with `@default`',
+        section_ids: ['default']
+    }
+]);
+
+writeDoc('vectors.json', {
+    version: 1,
+    dimensions: 1024,
+    prefix_length: 5,
+    idf: new Array(1024).fill(1),
+    docs: ['serde', 'intro'],
+    vectors: [
+        { buckets: [126, 891], weights: [0.7071, 0.7071] },
+        { buckets: [300], weights: [1] }
+    ]
+});
+
+writeDoc('aliases.json', {
+    version: 1,
+    aliases: {
+        'partial eq': { ids: ['partial-eq'], sources: ['href'] },
+        equals: { ids: ['partial-eq/equals', 'partial-eq'], sources: ['synonym'] }
+    }
+});
+
+writeDoc('examples.json', [
+    {
+        id: 'installation#example-1',
+        section_id: 'installation',
+        language: 'bash',
+        kind: 'shell',
+        heading: 'Vite',
+        code: 'npm install @macroforge/vite-plugin'
+    },
+    {
+        id: 'installation#example-2',
+        section_id: 'installation',
+        language: 'typescript',
+        kind: 'config',
+        file_name: 'vite.config.ts',
+        heading: 'Vite',
+        context: 'Register the plugin in your Vite config.',
+        code: 'export default defineConfig({ plugins: [macroforge()] });'
+    },
+    {
+        id: 'serialize#example-1',
+        section_id: 'serialize',
+        language: 'typescript',
+        kind: 'input',
+        code: '/** @derive(Serialize) */\nclass User {}'
+    }
+]);
+
+writeDoc('macroforge.schema.json', {
+    type: 'object',
+    properties: {
+        allowNativeMacros: { type: 'boolean', 'x-section-id': 'configuration/native' },
+        mode: { type: 'string', enum: ['strict', 'loose'], 'x-section-id': 'configuration/mode' },
+        limits: {
+            type: 'object',
+            properties: { maxDiagnostics: { type: 'integer' } },
+            additionalProperties: false,
+            'x-section-id': 'configuration/limits'
+        },
+        macroPackages: { type: 'array', items: { type: 'string' } }
+    },
+    additionalProperties: false
+});
+
+/** A macros.json entry */
+function macroInfo(name, sectionId, decorator) {
+    return {
+        name,
+        section_id: sectionId,
+        description: `${name} macro`,
+        decorator,
+        targets: ['class'],
+        generated: [],
+        options: [],
+        example_pairs: []
+    };
+}
+
+writeDoc('macros.json', [
+    macroInfo('Serialize', 'serialize', '@serde'),
+    macroInfo('Deserialize', 'deserialize', '@serde'),
+    macroInfo('PartialEq', 'partial-eq')
+]);
+
+process.env.MACROFORGE_DOCS_DIR = docsDir;
+const loader = await import('../dist/tools/docs-loader.js');
+
+test('checkSerdeUsage - likely typos are warnings with a suggestion', () => {
+    const issues = loader.checkSerdeUsage(
+        '/** @serde({ renam: "id", validate: { emial: true } }) */'
+    );
+
+    assert.deepEqual(
+        issues.map((i) => [i.level, i.suggestion]),
+        [['warning', 'rename'], ['warning', 'email']]
+    );
+    assert.equal(issues[0].line, 1);
+    assert.equal(issues[0].column, 14);
+});
+
+test('checkSerdeUsage - undocumented names are only info', () => {
+    const issues = loader.checkSerdeUsage('/** @serde(skip, transparent, validate(nonBlank)) */');
+
+    assert.deepEqual(issues.map((i) => i.level), ['info', 'info']);
+    assert.ok(issues.every((i) => i.suggestion === undefined));
+    assert.match(issues[0].help, /rename, skip, skipSerializing, validate/);
+});
+
+test('explainDiagnostic - patterns are anchored to the whole message', () => {
+    assert.equal(loader.explainDiagnostic('Unknown derive macro: Foo')?.id, 'cli#error-1');
+    assert.equal(loader.explainDiagnostic('note: Unknown derive macro: Foo in bar'), undefined);
+});
+
+test('explainDiagnostic - invalid patterns are skipped, keywords still match', () => {
+    assert.equal(loader.explainDiagnostic('(unclosed'), undefined);
+    assert.equal(
+        loader.explainDiagnostic('Enum Status has no variant marked with @default')?.id,
+        'default#error-1'
+    );
+});
+
+test('tokenize - matches the extractor index_terms', () => {
+    // Same input and terms as index_terms_include_identifier_parts in extract-docs.rs
+    const stopWords = new Set(['for', 'use', 'with']);
+    assert.deepEqual(
+        loader.tokenize('Use serializeWithContext for HTMLParser caching', stopWords),
+        ['serializewithcontext', 'serializ', 'context', 'htmlparser', 'html', 'parser', 'cach']
+    );
+});
+
+test('rankWithIndex - title matches outrank body matches', () => {
+    const index = {
+        version: 1,
+        fields: ['title', 'headings', 'keywords', 'body'],
+        weights: [3, 2, 2, 1],
+        k1: 1.2,
+        b: 0.75,
+        stop_words: [],
+        docs: ['intro', 'validation', 'missing'],
+        lengths: [[1, 0, 0, 20], [1, 0, 0, 20], [1, 0, 0, 20]],
+        avg_lengths: [1, 0, 0, 20],
+        postings: { validat: [0, 0, 0, 0, 2, 1, 1, 0, 0, 0, 2, 1, 0, 0, 0] }
+    };
+    const sections = ['intro', 'validation'].map((id) => ({ id, title: id }));
+
+    const ranked = loader.rankWithIndex(sections, index, 'validate');
+    assert.deepEqual(ranked.map((s) => s.id), ['validation', 'intro']);
+});
+
+test('embedQuery - uses the extractor feature buckets', () => {
+    // Buckets pinned by feature_buckets_are_fnv1a in extract-docs.rs
+    const embedding = loader.embedQuery(loader.loadSectionVectors(), 'serialize', new Set());
+
+    assert.deepEqual([...embedding.keys()], [891, 126]);
+    assert.ok(Math.abs(embedding.get(126) - Math.SQRT1_2) < 1e-9);
+});
+
+test('findSimilarSections - ranks by cosine similarity', () => {
+    const sections = ['serde', 'intro'].map((id) => ({ id, title: id }));
+
+    const similar = loader.findSimilarSections(sections, 'serialize');
+    assert.deepEqual(similar.map((r) => r.section.id), ['serde']);
+    assert.ok(Math.abs(similar[0].score - 1) < 1e-3);
+    assert.equal(loader.findSimilarSections(sections, 'unrelated').length, 0);
+});
+
+test('resolveAlias - normalizes case and whitespace', () => {
+    assert.deepEqual(loader.resolveAlias('  Partial   EQ '), ['partial-eq']);
+    assert.deepEqual(loader.resolveAlias('unknown'), []);
+});
+
+test('getSection - falls back to the first alias target present', () => {
+    const sections = ['partial-eq', 'serialize'].map((id) => ({ id, title: id }));
+
+    assert.equal(loader.getSection(sections, 'equals')?.id, 'partial-eq');
+    assert.equal(loader.getSection(sections, 'Partial Eq')?.id, 'partial-eq');
+});
+
+test('findExamples - scores headings and prose above code', () => {
+    assert.deepEqual(
+        loader.findExamples('vite plugin').map((e) => e.id),
+        ['installation#example-2', 'installation#example-1']
+    );
+    assert.deepEqual(
+        loader.findExamples('vite', { kind: 'shell' }).map((e) => e.id),
+        ['installation#example-1']
+    );
+    assert.deepEqual(
+        loader.findExamples('', { language: 'TypeScript' }).map((e) => e.id),
+        ['installation#example-2', 'serialize#example-1']
+    );
+});
+
+test('validateConfig - reports unknown keys, wrong types and enum values', () => {
+    assert.deepEqual(loader.validateConfig({ allowNativeMacros: true, mode: 'strict' }), []);
+
+    const issues = loader.validateConfig({
+        allowNativeMacros: 'yes',
+        mode: 'fast',
+        limits: { maxDiagnostics: 1.5, maxErrors: 3 },
+        macroPackages: ['a', 2]
+    });
+    assert.deepEqual(
+        issues.map((i) => [i.path, i.section_id]),
+        [
+            ['allowNativeMacros', 'configuration/native'],
+            ['mode', 'configuration/mode'],
+            ['limits.maxDiagnostics', 'configuration/limits'],
+            ['limits.maxErrors', 'configuration/limits'],
+            ['macroPackages.1', undefined]
+        ]
+    );
+    assert.match(issues[3].message, /^Unknown option `maxErrors`. Known options: maxDiagnostics$/);
+});
+
+test('findMacros - matches names, section IDs, derive forms and decorators', () => {
+    const names = (query) => loader.findMacros(query).map((m) => m.name);
+
+    assert.deepEqual(names('serialize'), ['Serialize']);
+    assert.deepEqual(names('partial-eq'), ['PartialEq']);
+    assert.deepEqual(names('@derive(PartialEq)'), ['PartialEq']);
+    assert.deepEqual(names('@serde'), ['Serialize', 'Deserialize']);
+    assert.deepEqual(names('Debug'), []);
+});